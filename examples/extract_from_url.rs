@@ -5,12 +5,12 @@ use std::env;
 use prouste::html::HtmlExtractor;
 
 fn main()  -> Result<(), Box<dyn std::error::Error>>{
-    let url = env::args().skip(1).next();
-    let body = reqwest::get(url.unwrap().as_str())?
+    let url = env::args().nth(1).unwrap();
+    let body = reqwest::get(url.as_str())?
         .text()?;
 
     let html_extractor = HtmlExtractor::default();
-    let article = html_extractor.from_string(body).unwrap();
+    let article = html_extractor.from_string_with_url(body, url.as_str())?;
     println!("title = {:?}", article.title);
     println!("top image = {:?}", article.top_image);
     println!("text = {:?}", article.text);
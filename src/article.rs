@@ -1,22 +1,169 @@
-use crate::embedding::Embedding;
+use std::collections::BTreeMap;
+
+use crate::embedding::{Embedding, Video};
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
 
 #[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Embeddings {
-    pub tweets: Vec<Embedding>,
-    pub instagram_posts: Vec<Embedding>,
+    /// Embedded posts keyed by the name of their `EmbedProvider`.
+    pub posts: BTreeMap<String, Vec<Embedding>>,
+    pub videos: Vec<Video>,
+}
+
+impl Embeddings {
+    pub fn get(&self, provider: &str) -> &[Embedding] {
+        self.posts.get(provider).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn tweets(&self) -> &[Embedding] {
+        self.get("twitter")
+    }
+
+    pub fn instagram_posts(&self) -> &[Embedding] {
+        self.get("instagram")
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Meta {
+    pub description: String,
+    pub og_type: String,
+    pub og_site_name: String,
+    pub og_url: String,
+    pub og_locale: String,
+    pub twitter_site: String,
+    pub twitter_creator: String,
+    /// Every `content` of the `<meta>` tags, keyed by their `property` or `name` attribute.
+    pub properties: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Link {
+    pub href: String,
+    pub text: String,
+    pub rel: Vec<String>,
+    pub title: String,
+    /// Whether the link points to another host than the one of the canonical link of the page.
+    pub is_external: bool,
+    /// Offset, in characters, of the anchor in the text of the article.
+    pub offset: usize,
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SrcsetCandidate {
+    pub url: String,
+    /// Width (`640w`) or pixel density (`2x`) descriptor, empty when absent.
+    pub descriptor: String,
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Image {
+    pub src: String,
+    pub srcset: Vec<SrcsetCandidate>,
+    pub alt: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub caption: String,
+    pub in_picture: bool,
+    pub in_figure: bool,
+}
+
+/// An image considered when no meta tag gives the top image, with the reasons of its score.
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ImageCandidate {
+    pub src: String,
+    pub score: i32,
+    pub selected: bool,
+    pub rejected: bool,
+    pub reasons: Vec<String>,
+}
+
+/// A microdata or RDFa item, with the values of its properties keyed by property name.
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Item {
+    pub item_type: Vec<String>,
+    pub properties: BTreeMap<String, Vec<ItemValue>>,
+}
+
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub enum ItemValue {
+    Text(String),
+    Item(Item),
+}
+
+/// Separator of the blocks in `Article::text`.
+pub const BLOCK_SEPARATOR: &str = "\n\n";
+
+#[derive(PartialEq, Debug, Clone, Copy, Deserialize, Serialize)]
+pub enum BlockKind {
+    /// A `<h1>` to `<h6>` heading, with its level.
+    Heading(u8),
+    Paragraph,
+    ListItem,
+    Quote,
+    /// Preformatted text, with its line breaks and indentation kept.
+    Code,
+    /// A data table, one row per line and the cells separated by tabs.
+    Table,
+    /// The caption of a figure.
+    Figure,
+}
+
+/// A block of the main content of the page, such as a paragraph or a heading.
+#[derive(PartialEq, Debug, Clone, Deserialize, Serialize)]
+pub struct Block {
+    pub kind: BlockKind,
+    pub text: String,
+}
+
+/// Joins the texts of the blocks into the flat text of the article.
+pub fn get_blocks_text(blocks: &[Block]) -> String {
+    blocks.iter()
+        .map(|block| block.text.as_str())
+        .collect::<Vec<&str>>()
+        .join(BLOCK_SEPARATOR)
 }
 
 #[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Article {
     pub title: String,
+    /// The texts of the blocks of `body`, separated by a blank line.
     pub text: String,
+    pub body: Vec<Block>,
+    /// The main content rendered as CommonMark, empty unless `enable_markdown_output` is set.
+    pub markdown: String,
+    /// The main content as a sanitized HTML fragment, empty unless `enable_html_output` is set.
+    pub html: String,
+    /// The language tag declared by the page with `<html lang>` or `content-language`, verbatim,
+    /// such as `pt-BR` or `en_US`. Empty when the page declares none.
     pub language: String,
+    /// The ISO 639-1 code of the declared language, or of the detected one when the page declares
+    /// none.
+    pub language_code: String,
+    /// Whether `language_code` comes from the declared tag rather than from the detection on the
+    /// text.
+    pub language_declared: bool,
+    /// 1 for a declared language, the confidence of the detection otherwise.
+    pub language_confidence: f64,
     pub favico: String,
     pub canonical_link: String,
+    /// The oEmbed endpoints advertised by the page with `<link rel="alternate">`.
+    pub oembed_links: Vec<String>,
     pub meta_keywords: String,
     pub top_image: String,
-    pub links: Vec<String>,
+    pub top_image_candidates: Vec<ImageCandidate>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub raw_published_at: String,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub raw_modified_at: String,
+    pub authors: Vec<String>,
+    pub meta: Meta,
+    pub structured_data: Vec<Value>,
+    pub microdata: Vec<Item>,
+    pub links: Vec<Link>,
+    pub images: Vec<Image>,
     pub embeddings: Embeddings,
 }
 
@@ -1,9 +1,250 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How a custom stopword set is combined with the built-in list of its language.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopwordsMode {
+    /// The custom words are added to the built-in list.
+    Merge,
+    /// The custom words are used instead of the built-in list.
+    Replace,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomStopwords {
+    pub words: Vec<String>,
+    pub mode: StopwordsMode,
+}
+
+const ALLOWED_TAGS: [&str; 46] = [
+    "a", "abbr", "b", "blockquote", "br", "caption", "cite", "code", "dd", "del", "dl", "dt", "em",
+    "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6", "hr", "i", "img", "ins", "kbd",
+    "li", "mark", "ol", "p", "pre", "q", "s", "small", "strong", "sub", "sup", "table", "tbody",
+    "td", "tfoot", "th", "thead", "time", "tr", "ul",
+];
+
+const ALLOWED_ATTRIBUTES: [(&str, &str); 16] = [
+    ("a", "href"),
+    ("a", "title"),
+    ("abbr", "title"),
+    ("blockquote", "cite"),
+    ("img", "alt"),
+    ("img", "height"),
+    ("img", "src"),
+    ("img", "srcset"),
+    ("img", "width"),
+    ("ol", "start"),
+    ("q", "cite"),
+    ("td", "colspan"),
+    ("td", "rowspan"),
+    ("th", "colspan"),
+    ("th", "rowspan"),
+    ("time", "datetime"),
+];
+
+/// The tags and attributes kept in the clean HTML of the main content. A tag out of the
+/// allowlist is replaced by its content. Event handlers and `style` attributes are never kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtmlAllowlist {
+    pub tags: BTreeSet<String>,
+    /// Attributes kept, by tag.
+    pub attributes: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Default for HtmlAllowlist {
+    fn default() -> Self {
+        let mut allowlist = HtmlAllowlist { tags: BTreeSet::new(), attributes: BTreeMap::new() };
+        for tag in ALLOWED_TAGS.iter() {
+            allowlist.allow_tag(tag);
+        }
+        for (tag, attribute) in ALLOWED_ATTRIBUTES.iter() {
+            allowlist.allow_attribute(tag, attribute);
+        }
+        allowlist
+    }
+}
+
+impl HtmlAllowlist {
+    pub fn allow_tag(&mut self, tag: &str) {
+        self.tags.insert(tag.to_lowercase());
+    }
+
+    /// Allows an attribute on a tag, and the tag itself.
+    pub fn allow_attribute(&mut self, tag: &str, attribute: &str) {
+        self.allow_tag(tag);
+        self.attributes.entry(tag.to_lowercase()).or_default().insert(attribute.to_lowercase());
+    }
+
+    pub fn is_allowed_attribute(&self, tag: &str, attribute: &str) -> bool {
+        self.attributes.get(tag).is_some_and(|attributes| attributes.contains(attribute))
+    }
+}
+
+const BOILERPLATE_TAGS: [&str; 3] = ["aside", "footer", "nav"];
+
+const BOILERPLATE_CLASS_PATTERNS: [&str; 11] = [
+    "comment", "cookie", "footer", "nav", "newsletter", "promo", "related", "share", "sidebar",
+    "social", "subscribe",
+];
+
+const BOILERPLATE_ROLES: [&str; 3] = ["complementary", "contentinfo", "navigation"];
+
+/// The nodes left out before looking for the main content, such as comments, share buttons or
+/// newsletter sign-ups. A node holding an `<article>` or a `<main>` is never left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoilerplatePatterns {
+    pub tags: BTreeSet<String>,
+    /// Prefixes of the words of the `class` and `id` attributes, matched case-insensitively:
+    /// `share` matches `social-share` and `share_buttons` but not `timeshare`.
+    pub class_patterns: BTreeSet<String>,
+    /// Values of the `role` attribute.
+    pub roles: BTreeSet<String>,
+}
+
+impl Default for BoilerplatePatterns {
+    fn default() -> Self {
+        let to_set = |values: &[&str]| values.iter().map(|value| String::from(*value)).collect();
+        BoilerplatePatterns {
+            tags: to_set(&BOILERPLATE_TAGS),
+            class_patterns: to_set(&BOILERPLATE_CLASS_PATTERNS),
+            roles: to_set(&BOILERPLATE_ROLES),
+        }
+    }
+}
+
+impl BoilerplatePatterns {
+    /// No node is left out.
+    pub fn none() -> Self {
+        BoilerplatePatterns { tags: BTreeSet::new(), class_patterns: BTreeSet::new(), roles: BTreeSet::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.class_patterns.is_empty() && self.roles.is_empty()
+    }
+}
+
+/// The normalization of the whitespace and typography of the text of the article. The whitespace
+/// of the code blocks is always kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextNormalization {
+    /// Replaces the runs of whitespace, non-breaking spaces and source line breaks included, by a
+    /// single space.
+    pub collapse_whitespace: bool,
+    /// Removes the spaces at the start and at the end of the lines.
+    pub trim_lines: bool,
+    /// Merges more than two consecutive line breaks into two.
+    pub merge_newlines: bool,
+    /// Composes the characters to the Unicode normalization form C.
+    pub unicode_nfc: bool,
+    /// Replaces the curly quotes by straight ones.
+    pub replace_smart_quotes: bool,
+    pub remove_soft_hyphens: bool,
+    /// Removes the zero-width spaces, joiners and non-joiners, the word joiners and the byte order
+    /// marks.
+    pub remove_zero_width_characters: bool,
+}
+
+impl Default for TextNormalization {
+    fn default() -> Self {
+        TextNormalization {
+            collapse_whitespace: true,
+            trim_lines: true,
+            merge_newlines: true,
+            unicode_nfc: false,
+            replace_smart_quotes: false,
+            remove_soft_hyphens: false,
+            remove_zero_width_characters: false,
+        }
+    }
+}
+
 pub struct Configuration {
     pub enable_text_extraction: bool,
     pub enable_embeddings_extraction: bool,
     pub enable_meta_extraction: bool,
+    pub boilerplate_patterns: BoilerplatePatterns,
+    pub text_normalization: TextNormalization,
+    /// Renders the main content as CommonMark in `Article::markdown`, along with the text.
+    pub enable_markdown_output: bool,
+    /// Renders the main content as a sanitized HTML fragment in `Article::html`.
+    pub enable_html_output: bool,
+    pub html_allowlist: HtmlAllowlist,
+    /// Stopword sets registered per language code, used to find the main content of the page.
+    pub custom_stopwords: BTreeMap<String, CustomStopwords>,
 }
 
 impl Default for Configuration {
-    fn default() -> Self { Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true } }
-}
\ No newline at end of file
+    fn default() -> Self {
+        Configuration {
+            enable_text_extraction: true,
+            enable_embeddings_extraction: true,
+            enable_meta_extraction: true,
+            boilerplate_patterns: BoilerplatePatterns::default(),
+            text_normalization: TextNormalization::default(),
+            enable_markdown_output: false,
+            enable_html_output: false,
+            html_allowlist: HtmlAllowlist::default(),
+            custom_stopwords: BTreeMap::new(),
+        }
+    }
+}
+
+impl Configuration {
+    /// Registers stopwords for a language. The words registered several times for the same
+    /// language are accumulated, and the mode of the last registration is kept.
+    pub fn add_stopwords(&mut self, lang: &str, words: &[&str], mode: StopwordsMode) {
+        let custom_stopwords = self.custom_stopwords.entry(lang.trim().to_lowercase())
+            .or_insert(CustomStopwords { words: Vec::new(), mode });
+        custom_stopwords.mode = mode;
+        custom_stopwords.words.extend(words.iter()
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty()));
+        custom_stopwords.words.sort();
+        custom_stopwords.words.dedup();
+    }
+
+    /// Registers the stopwords of a newline-delimited file for a language.
+    pub fn add_stopwords_from_file<P: AsRef<Path>>(&mut self, lang: &str, path: P, mode: StopwordsMode) -> io::Result<()> {
+        let list = fs::read_to_string(path)?;
+        let words: Vec<&str> = list.lines().collect();
+        self.add_stopwords(lang, &words, mode);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_stopwords() {
+        let mut configuration = Configuration::default();
+        configuration.add_stopwords("pt-BR", &["Você", "  ", "a"], StopwordsMode::Merge);
+        configuration.add_stopwords("PT-br", &["cê", "a"], StopwordsMode::Replace);
+        let custom_stopwords = &configuration.custom_stopwords["pt-br"];
+        assert_eq!(custom_stopwords.words, vec!["a", "cê", "você"]);
+        assert_eq!(custom_stopwords.mode, StopwordsMode::Replace);
+    }
+
+    #[test]
+    fn test_html_allowlist() {
+        let mut allowlist = HtmlAllowlist::default();
+        assert!(allowlist.tags.contains("blockquote"));
+        assert!(!allowlist.tags.contains("div"));
+        assert!(allowlist.is_allowed_attribute("a", "href"));
+        assert!(!allowlist.is_allowed_attribute("a", "onclick"));
+        allowlist.allow_attribute("DIV", "Lang");
+        assert!(allowlist.tags.contains("div"));
+        assert!(allowlist.is_allowed_attribute("div", "lang"));
+    }
+
+    #[test]
+    fn test_add_stopwords_from_file() {
+        let mut configuration = Configuration::default();
+        configuration.add_stopwords_from_file("pl", "src/extraction/stopwords/pl.txt", StopwordsMode::Merge).unwrap();
+        assert!(configuration.custom_stopwords["pl"].words.contains(&String::from("się")));
+        assert!(configuration.add_stopwords_from_file("pl", "missing.txt", StopwordsMode::Merge).is_err());
+    }
+}
@@ -1,79 +1,399 @@
+use std::collections::BTreeMap;
+
 use regex::Regex;
 use select::document::Document;
 use select::node::Node;
-use select::predicate::{Name, Predicate};
+use select::predicate::{Attr, Name, Predicate};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use super::select::predicate::{Child, Class};
 
 lazy_static! {
     static ref SPACES_REGEX: Regex = Regex::new(r"\s\s+").unwrap();
+    static ref YOUTUBE_REGEX: Regex = Regex::new(r"(?:youtube(?:-nocookie)?\.com/(?:embed/|v/|watch\?v=)|youtu\.be/)([\w-]{11})").unwrap();
+    static ref VIMEO_REGEX: Regex = Regex::new(r"vimeo\.com/(?:video/)?(\d+)").unwrap();
+    static ref TWEET_STATUS_REGEX: Regex = Regex::new(r"/status(?:es)?/(\d+)").unwrap();
+    static ref TWEET_AUTHOR_REGEX: Regex = Regex::new(r"(?s)^[\s—-]*(.*?)\s*\((@\w+)\)").unwrap();
+    static ref TWEET_MEDIA_REGEX: Regex = Regex::new(r"/status/\d+/(?:photo|video)/\d+").unwrap();
+    static ref DAILYMOTION_REGEX: Regex = Regex::new(r"(?:dailymotion\.com/(?:embed/)?video/|dai\.ly/)([a-zA-Z0-9]+)").unwrap();
 }
 
 #[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Embedding {
+    /// Name of the `EmbedProvider` which found the embedding: `twitter`, `instagram`, `facebook`,
+    /// `tiktok`, `reddit`, `mastodon`, `threads`, `bluesky` or a custom one.
+    pub provider: String,
     pub url: String,
     pub text: String,
+    /// Identifier of the post on its platform, such as the numeric status ID of a tweet.
+    pub id: String,
+    pub author_name: String,
+    pub author_handle: String,
+    /// Date of the post as displayed in the embed, e.g. `June 24, 2016`.
+    pub date: String,
+    pub lang: String,
+    pub dir: String,
+    pub media: Vec<String>,
+    pub title: String,
+    pub thumbnail: String,
 }
 
-pub fn get_tweets(document: &Document) -> Vec<Embedding> {
-    let mut embeddings: Vec<Embedding> = Vec::new();
-    let blockquote_predicate = Name("blockquote");
-    let p_predicate = Name("p");
-    let child_link_predicate = Child(blockquote_predicate, Name("a"));
-    for tag in document.find(blockquote_predicate.and(Class("twitter-tweet"))) {
-        let mut text: String = String::default();
-        let mut url: String = String::default();
-        if let Some(node) = tag.find(p_predicate).next() {
-             text = get_sanitized_text(node);
+impl Embedding {
+    /// Fills the fields left empty by the extraction with the oEmbed data of the post.
+    pub fn enrich(&mut self, oembed: &OEmbed) {
+        if self.title.is_empty() {
+            self.title = oembed.title.clone();
+        }
+        if self.author_name.is_empty() {
+            self.author_name = oembed.author_name.clone();
         }
-        if let Some(node) = tag.find(child_link_predicate).next() {
-            url = get_href(node);
+        if self.thumbnail.is_empty() {
+            self.thumbnail = oembed.thumbnail_url.clone();
         }
-        embeddings.push(Embedding { url, text })
     }
-    embeddings
 }
 
-pub fn get_instagram_posts(document: &Document) -> Vec<Embedding> {
-    let mut embeddings: Vec<Embedding> = Vec::new();
-    let child_link_predicate = Child(Name("p"), Name("a"));
-    for tag in document.find(Name("blockquote").and(Class("instagram-media"))) {
-        if let Some(node) = tag.find(child_link_predicate).next() {
-            embeddings.push(Embedding {
-                url: get_href(node),
-                text: get_sanitized_text(node),
-            });
+/// The subset of an oEmbed response used to enrich the embeddings.
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OEmbed {
+    pub title: String,
+    pub author_name: String,
+    pub thumbnail_url: String,
+}
+
+/// Looks up the oEmbed data of a post URL. The crate never performs any request by itself:
+/// fetching, caching and choosing the endpoint are up to the implementation.
+pub trait OEmbedResolver: Send + Sync {
+    fn resolve(&self, url: &str) -> Option<OEmbed>;
+}
+
+#[derive(PartialEq, Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Video {
+    /// `youtube`, `vimeo`, `dailymotion` or `video` for the HTML `<video>` elements.
+    pub provider: String,
+    pub url: String,
+    pub id: String,
+    pub title: String,
+    pub thumbnail: String,
+}
+
+/// Recognizes the embedded posts of a platform. Every node of the document matching the provider
+/// is handed to `extract`; the resulting embeddings are grouped under the provider `name`, which
+/// is also set as their `provider`. `extract` returns `None` for an embed code without a URL.
+pub trait EmbedProvider: Send + Sync {
+    fn name(&self) -> &str;
+    fn matches(&self, node: &Node) -> bool;
+    fn extract(&self, node: &Node) -> Option<Embedding>;
+}
+
+pub struct TweetProvider;
+
+impl EmbedProvider for TweetProvider {
+    fn name(&self) -> &str { "twitter" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("blockquote").and(Class("twitter-tweet")).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        let mut embedding = Embedding::default();
+        if let Some(p) = node.find(Name("p")).next() {
+            embedding.text = get_sanitized_text(p);
+            embedding.lang = String::from(p.attr("lang").unwrap_or_default());
+            embedding.dir = String::from(p.attr("dir").unwrap_or_default());
+            embedding.media = p.find(Name("a"))
+                .filter(|link| is_tweet_media_link(link))
+                .map(get_href)
+                .collect();
+        }
+        if let Some(link) = node.find(Child(Name("blockquote"), Name("a"))).next() {
+            embedding.url = get_href(link);
+            embedding.date = get_sanitized_text(link);
+            if let Some(captures) = TWEET_STATUS_REGEX.captures(&embedding.url) {
+                embedding.id = String::from(&captures[1]);
+            }
+        }
+        let byline: String = node.children()
+            .filter(|child| child.name().is_none())
+            .map(|child| child.text())
+            .collect();
+        if let Some(captures) = TWEET_AUTHOR_REGEX.captures(&byline) {
+            embedding.author_name = String::from(SPACES_REGEX.replace_all(&captures[1], " "));
+            embedding.author_handle = String::from(&captures[2]);
+        }
+        with_url(embedding)
+    }
+}
+
+pub struct InstagramProvider;
+
+impl EmbedProvider for InstagramProvider {
+    fn name(&self) -> &str { "instagram" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("blockquote").and(Class("instagram-media")).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        node.find(Child(Name("p"), Name("a"))).next().and_then(|link| with_url(Embedding {
+            url: get_href(link),
+            text: get_sanitized_text(link),
+            ..Embedding::default()
+        }))
+    }
+}
+
+pub struct FacebookProvider;
+
+impl EmbedProvider for FacebookProvider {
+    fn name(&self) -> &str { "facebook" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("div").and(Class("fb-post").or(Class("fb-video"))).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        let text = match node.find(Name("p")).next() {
+            Some(p) => get_sanitized_text(p),
+            _ => get_sanitized_text(*node)
+        };
+        with_url(Embedding { url: String::from(node.attr("data-href").unwrap_or_default().trim()), text, ..Embedding::default() })
+    }
+}
+
+pub struct TikTokProvider;
+
+impl EmbedProvider for TikTokProvider {
+    fn name(&self) -> &str { "tiktok" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("blockquote").and(Class("tiktok-embed")).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        let text = match node.find(Name("p")).next() {
+            Some(p) => get_sanitized_text(p),
+            _ => get_sanitized_text(*node)
+        };
+        with_url(Embedding { url: String::from(node.attr("cite").unwrap_or_default().trim()), text, ..Embedding::default() })
+    }
+}
+
+pub struct RedditProvider;
+
+impl EmbedProvider for RedditProvider {
+    fn name(&self) -> &str { "reddit" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("blockquote").and(Class("reddit-card").or(Class("reddit-embed-bq"))).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        node.find(Name("a")).next().and_then(|link| with_url(Embedding {
+            url: get_href(link),
+            text: get_sanitized_text(link),
+            ..Embedding::default()
+        }))
+    }
+}
+
+pub struct MastodonProvider;
+
+impl EmbedProvider for MastodonProvider {
+    fn name(&self) -> &str { "mastodon" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("iframe").and(Class("mastodon-embed")).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        let src = node.attr("src").unwrap_or_default().trim();
+        with_url(Embedding { url: String::from(src.trim_end_matches('/').trim_end_matches("/embed")), ..Embedding::default() })
+    }
+}
+
+pub struct ThreadsProvider;
+
+impl EmbedProvider for ThreadsProvider {
+    fn name(&self) -> &str { "threads" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("blockquote").and(Class("text-post-media")).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        let url = node.attr("data-text-post-permalink").unwrap_or_default();
+        // The embed code only holds a "View on Threads" placeholder, the text is loaded by its script.
+        let text = node.find(Name("p")).next().map(get_sanitized_text).unwrap_or_default();
+        with_url(Embedding { url: String::from(url.trim()), text, ..Embedding::default() })
+    }
+}
+
+pub struct BlueskyProvider;
+
+impl EmbedProvider for BlueskyProvider {
+    fn name(&self) -> &str { "bluesky" }
+
+    fn matches(&self, node: &Node) -> bool {
+        Name("blockquote").and(Class("bluesky-embed")).matches(node)
+    }
+
+    fn extract(&self, node: &Node) -> Option<Embedding> {
+        let mut text: String = String::default();
+        if let Some(p) = node.find(Name("p")).next() {
+            text = get_sanitized_text(p);
+        }
+        let url = match node.find(Name("a")).filter(|a| a.attr("href").unwrap_or_default().contains("/post/")).last() {
+            Some(link) => get_href(link),
+            _ => String::from(node.attr("data-bluesky-uri").unwrap_or_default().trim())
+        };
+        with_url(Embedding { url, text, ..Embedding::default() })
+    }
+}
+
+pub static BUILTIN_PROVIDERS: &[&dyn EmbedProvider] = &[
+    &TweetProvider,
+    &InstagramProvider,
+    &FacebookProvider,
+    &TikTokProvider,
+    &RedditProvider,
+    &MastodonProvider,
+    &ThreadsProvider,
+    &BlueskyProvider,
+];
+
+/// Runs the providers over the document and groups their embeddings by provider name. Providers
+/// without any match are left out of the map.
+pub fn get_embeddings(document: &Document, providers: &[&dyn EmbedProvider]) -> BTreeMap<String, Vec<Embedding>> {
+    let mut embeddings: BTreeMap<String, Vec<Embedding>> = BTreeMap::new();
+    for provider in providers {
+        let name = provider.name();
+        for node in document.find(|node: &Node| provider.matches(node)) {
+            if let Some(mut embedding) = provider.extract(&node) {
+                embedding.provider = String::from(name);
+                embeddings.entry(String::from(name)).or_default().push(embedding);
+            }
         }
     }
     embeddings
 }
 
+fn is_tweet_media_link(link: &Node) -> bool {
+    let href = link.attr("href").unwrap_or_default();
+    link.text().trim().starts_with("pic.twitter.com/")
+        || href.contains("pic.twitter.com/")
+        || TWEET_MEDIA_REGEX.is_match(href)
+}
+
 fn get_href(node: Node) -> String {
-    String::from(node.attr("href").unwrap_or(""))
+    String::from(node.attr("href").unwrap_or("").trim())
+}
+
+fn with_url(embedding: Embedding) -> Option<Embedding> {
+    if embedding.url.is_empty() { None } else { Some(embedding) }
 }
 
 fn get_sanitized_text(node: Node) -> String {
     String::from(SPACES_REGEX.replace_all(node.text().trim(), " "))
 }
-/*
-pub(crate) fn get_youtube_videos(document: &Document) -> Vec<Embedding> {
-    let mut embeddings: Vec<Embedding> = Vec::new();
-    let re = Regex::new(r"\s\s+").unwrap();
-    for tag in document.find(Name("iframe").and(Attr("data-provider", "youtube"))) {
-        match tag.find(Child(Name("p"), Name("a"))).next() {
-            Some(node) => {
-                embeddings.push(Embedding {
-                    url: String::from(node.attr("src").unwrap_or("")),
-                    text:  String::from(node.attr("data-title").unwrap_or("")),
-                });
+
+pub fn get_videos(document: &Document) -> Vec<Video> {
+    let mut videos: Vec<Video> = Vec::new();
+    push_player_videos(document, &mut videos);
+    for fragment in get_json_html_fragments(document) {
+        push_player_videos(&Document::from(fragment.as_str()), &mut videos);
+    }
+    for tag in document.find(Name("video")) {
+        let src = match tag.attr("src") {
+            Some(src) => Some(src),
+            _ => tag.find(Name("source").and(Attr("type", "video/mp4"))).chain(tag.find(Name("source")))
+                .filter_map(|source| source.attr("src"))
+                .next()
+        };
+        if let Some(src) = src {
+            videos.push(Video {
+                provider: String::from("video"),
+                url: String::from(src.trim()),
+                id: String::default(),
+                title: get_video_title(tag),
+                thumbnail: String::from(tag.attr("poster").unwrap_or_default()),
+            });
+        }
+    }
+    videos
+}
+
+fn push_player_videos(document: &Document, videos: &mut Vec<Video>) {
+    let player_predicate = Name("iframe").or(Name("embed")).or(Name("param").and(Attr("name", "movie")));
+    for tag in document.find(player_predicate) {
+        let src = tag.attr("src").or_else(|| tag.attr("data-src")).or_else(|| tag.attr("value")).unwrap_or_default();
+        if let Some(mut video) = get_provider_video(src) {
+            video.title = get_video_title(tag);
+            if !videos.iter().any(|v| v.provider == video.provider && v.id == video.id) {
+                videos.push(video);
             }
-            _ => ()
         }
     }
-    return embeddings;
 }
-*/
+
+/// Pages rendered on the client, such as the Next.js ones, keep the HTML of their embeds escaped
+/// in the JSON state of a `<script type="application/json">`, e.g. `"sourceCode": "<object>…"`.
+fn get_json_html_fragments(document: &Document) -> Vec<String> {
+    let mut fragments: Vec<String> = Vec::new();
+    for script in document.find(Name("script").and(Attr("type", "application/json"))) {
+        if let Ok(value) = serde_json::from_str::<Value>(&script.text()) {
+            collect_html_fragments(&value, &mut fragments);
+        }
+    }
+    fragments
+}
+
+fn collect_html_fragments(value: &Value, fragments: &mut Vec<String>) {
+    match value {
+        Value::String(s) if s.contains("<iframe") || s.contains("<embed") || s.contains("<param") => fragments.push(s.to_owned()),
+        Value::Array(values) => values.iter().for_each(|v| collect_html_fragments(v, fragments)),
+        Value::Object(map) => map.values().for_each(|v| collect_html_fragments(v, fragments)),
+        _ => ()
+    }
+}
+
+fn get_provider_video(src: &str) -> Option<Video> {
+    if let Some(captures) = YOUTUBE_REGEX.captures(src) {
+        let id = &captures[1];
+        return Some(Video {
+            provider: String::from("youtube"),
+            url: format!("https://www.youtube.com/watch?v={}", id),
+            id: String::from(id),
+            title: String::default(),
+            thumbnail: format!("https://img.youtube.com/vi/{}/hqdefault.jpg", id),
+        });
+    }
+    if let Some(captures) = VIMEO_REGEX.captures(src) {
+        let id = &captures[1];
+        return Some(Video {
+            provider: String::from("vimeo"),
+            url: format!("https://vimeo.com/{}", id),
+            id: String::from(id),
+            ..Video::default()
+        });
+    }
+    if let Some(captures) = DAILYMOTION_REGEX.captures(src) {
+        let id = &captures[1];
+        return Some(Video {
+            provider: String::from("dailymotion"),
+            url: format!("https://www.dailymotion.com/video/{}", id),
+            id: String::from(id),
+            title: String::default(),
+            thumbnail: format!("https://www.dailymotion.com/thumbnail/video/{}", id),
+        });
+    }
+    None
+}
+
+fn get_video_title(node: Node) -> String {
+    String::from(node.attr("data-title").or_else(|| node.attr("title")).unwrap_or_default().trim())
+}
 
 #[cfg(test)]
 mod tests {
@@ -81,36 +401,153 @@ mod tests {
 
     use super::*;
 
+    fn get_provider_embeddings(document: &Document, provider: &dyn EmbedProvider) -> Vec<Embedding> {
+        get_embeddings(document, &[provider]).remove(provider.name()).unwrap_or_default()
+    }
+
     #[test]
     fn get_tweets_telegraph() {
         let document = Document::from(include_str!("extraction/sites/telegraph.co.uk.html"));
-        let tweets = get_tweets(&document);
+        let tweets = get_provider_embeddings(&document, &TweetProvider);
 
         assert_eq!(tweets.len(), 35);
-        let tweet = tweets.get(0).unwrap();
+        let tweet = tweets.first().unwrap();
+        assert_eq!(tweet.provider, "twitter");
         assert_eq!(tweet.url, "https://twitter.com/lindsaylohan/status/746167573453094912");
         assert_eq!(tweet.text, "One thing for sure the #referendum results are very close, but also showing a difference of opinion across #Britain");
+        assert_eq!(tweet.id, "746167573453094912");
+        assert_eq!(tweet.author_name, "Lindsay Lohan");
+        assert_eq!(tweet.author_handle, "@lindsaylohan");
+        assert_eq!(tweet.date, "June 24, 2016");
+        assert_eq!(tweet.lang, "en");
+        assert_eq!(tweet.dir, "ltr");
+        assert!(tweet.media.is_empty());
+        assert_eq!(tweets[1].lang, "und");
+        assert!(tweets.iter().all(|t| !t.id.is_empty() && t.author_handle.starts_with('@')));
+
+        let tweet = tweets.iter().find(|t| t.id == "746133979904999425").unwrap();
+        assert_eq!(tweet.author_name, "Telegraph TV & Radio");
+        assert_eq!(tweet.author_handle, "@TeleTVRadio");
+        assert_eq!(tweet.media, vec!["https://t.co/SUDPdZxPEZ"]);
     }
 
     #[test]
     fn get_instagram_posts_telegraph() {
         let document = Document::from(include_str!("extraction/sites/telegraph.co.uk.html"));
 
-        let instagram_posts = get_instagram_posts(&document);
+        let instagram_posts = get_provider_embeddings(&document, &InstagramProvider);
         assert_eq!(instagram_posts.len(), 5);
-        let post = instagram_posts.get(0).unwrap();
+        let post = instagram_posts.first().unwrap();
         assert_eq!(post.url, "https://www.instagram.com/p/BHA-BtNh3h1/");
         assert_eq!(post.text, "#besmart pay attention and work hard to buy @chanelofficial #remain where's Sunderland? Does Sarah Palin live there? Lol");
     }
-    /*
-        #[test]
-        fn get_youtube_videos() {
-            let document = Document::from(include_str!("sites/figaro.fr.html"));
-
-            let videos = get_instagram_posts(&document);
-            assert_eq!(videos.len(), 5);
-            let video = videos.get(0).unwrap();
-            assert_eq!(video.url, "https://www.instagram.com/p/BHA-BtNh3h1/");
-            assert_eq!(video.text, "#besmart pay attention and work hard to buy @chanelofficial #remain where's Sunderland? Does Sarah Palin live there? Lol");
-        }*/
-}
\ No newline at end of file
+
+    #[test]
+    fn get_videos_youtube_iframe() {
+        let document = Document::from(include_str!("extraction/sites/dev4510c.html"));
+        let videos = get_videos(&document);
+
+        assert_eq!(videos.len(), 1);
+        let video = videos.first().unwrap();
+        assert_eq!(video.provider, "youtube");
+        assert_eq!(video.id, "qdWvggmWkGE");
+        assert_eq!(video.url, "https://www.youtube.com/watch?v=qdWvggmWkGE");
+        assert_eq!(video.thumbnail, "https://img.youtube.com/vi/qdWvggmWkGE/hqdefault.jpg");
+    }
+
+    #[test]
+    fn get_videos_youtube_json_figaro() {
+        let document = Document::from(include_str!("extraction/sites/figaro.fr.html"));
+        let videos = get_videos(&document);
+
+        assert_eq!(videos.len(), 2);
+        let video = videos.first().unwrap();
+        assert_eq!(video.provider, "youtube");
+        assert_eq!(video.id, "l-O1Uo14RWQ");
+        assert_eq!(video.url, "https://www.youtube.com/watch?v=l-O1Uo14RWQ");
+        assert_eq!(videos[1].provider, "dailymotion");
+        assert_eq!(videos[1].id, "xxgw0t");
+    }
+
+    #[test]
+    fn get_videos_html5_theguardian() {
+        let document = Document::from(include_str!("extraction/sites/theguardian.com.html"));
+        let videos = get_videos(&document);
+
+        assert_eq!(videos.len(), 1);
+        let video = videos.first().unwrap();
+        assert_eq!(video.provider, "video");
+        assert_eq!(video.url, "http://cdn.theguardian.tv/mainwebsite/2015/11/13/151113storm_desk.mp4");
+        assert!(video.thumbnail.starts_with("https://i.guim.co.uk/img/static/sys-images/Guardian/Pix/audio/video/2015/11/13/"));
+    }
+
+    #[test]
+    fn get_videos_providers() {
+        let document = Document::from(r#"<html><body>
+            <iframe src="https://player.vimeo.com/video/76979871" title="Vimeo video"></iframe>
+            <iframe src="http://www.dailymotion.com/embed/video/xxgw0t_sefyu-fdp?syndication=113906" data-title="Dailymotion video"></iframe>
+            <object><param name="movie" value="http://www.youtube.com/v/l-O1Uo14RWQ?version=3"><embed src="http://www.youtube.com/v/l-O1Uo14RWQ?version=3"></object>
+        </body></html>"#);
+        let videos = get_videos(&document);
+
+        assert_eq!(videos.len(), 3);
+        assert_eq!(videos[0].provider, "vimeo");
+        assert_eq!(videos[0].url, "https://vimeo.com/76979871");
+        assert_eq!(videos[0].title, "Vimeo video");
+        assert_eq!(videos[1].provider, "dailymotion");
+        assert_eq!(videos[1].id, "xxgw0t");
+        assert_eq!(videos[1].title, "Dailymotion video");
+        assert_eq!(videos[2].provider, "youtube");
+        assert_eq!(videos[2].id, "l-O1Uo14RWQ");
+    }
+
+    #[test]
+    fn get_embeddings_builtin_providers() {
+        let document = Document::from(include_str!("extraction/sites/telegraph.co.uk.html"));
+        let embeddings = get_embeddings(&document, BUILTIN_PROVIDERS);
+
+        assert_eq!(embeddings.keys().collect::<Vec<_>>(), vec!["instagram", "twitter"]);
+        assert_eq!(embeddings["twitter"].len(), 35);
+        assert_eq!(embeddings["instagram"].len(), 5);
+        assert!(embeddings["instagram"].iter().all(|e| e.provider == "instagram"));
+    }
+
+    #[test]
+    fn get_social_posts_social_embeds() {
+        let document = Document::from(include_str!("extraction/sites/social_embeds.html"));
+        let embeddings = get_embeddings(&document, BUILTIN_PROVIDERS);
+
+        let expected = [
+            ("bluesky", "https://bsky.app/profile/did:plc:z72i7hdynmk6r22z27h6tvur/post/3k44deefqdk2g?ref_src=embed",
+                "Some users are seeing errors when loading their feeds. We are looking into it and will post updates on the status page."),
+            ("facebook", "https://www.facebook.com/facebook/posts/pfbid02XZ1Jq7yJ3bRH8uaYjnCgeHZCm9vWt1VHDYAm9qU5NqmEyUgr2bSyEBG4pb6kN3nql",
+                "We’re aware that some people are having trouble accessing our apps and products."),
+            ("facebook", "https://www.facebook.com/facebook/videos/10153231379946729/", "An update from the engineering team."),
+            ("mastodon", "https://mastodon.social/@Gargron/110818045633744520", ""),
+            ("reddit", "https://www.reddit.com/r/technology/comments/15fj3ke/several_platforms_down_for_two_hours/", "Several platforms down for two hours"),
+            // The embed code only holds a "View on Threads" placeholder.
+            ("threads", "https://www.threads.net/@mosseri/post/CvZ1m0wLkQF", ""),
+            ("tiktok", "https://www.tiktok.com/@scout2015/video/6718335390845095173", "When the app is down and you have to talk to your family"),
+        ];
+        let posts: Vec<(&str, &str, &str)> = embeddings.values().flatten()
+            .map(|post| (post.provider.as_str(), post.url.as_str(), post.text.as_str()))
+            .collect();
+        assert_eq!(posts, expected);
+    }
+
+    #[test]
+    fn get_social_posts_without_url() {
+        let document = Document::from(r#"<html><body>
+            <blockquote class="twitter-tweet"><p lang="en">A tweet</p>&mdash; Someone (@someone)</blockquote>
+            <blockquote class="instagram-media"><p><a href=" ">A post</a></p></blockquote>
+            <div class="fb-post" data-href=""><blockquote><p>A post</p></blockquote></div>
+            <blockquote class="tiktok-embed"><section><p>A video</p></section></blockquote>
+            <blockquote class="reddit-card"><a>A thread</a></blockquote>
+            <iframe src="" class="mastodon-embed"></iframe>
+            <blockquote class="text-post-media" data-text-post-permalink=" "></blockquote>
+            <blockquote class="bluesky-embed"><p>A post</p></blockquote>
+        </body></html>"#);
+
+        assert!(get_embeddings(&document, BUILTIN_PROVIDERS).is_empty());
+    }
+}
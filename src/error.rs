@@ -0,0 +1,41 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ExtractionError {
+    /// The raw HTML or byte stream is empty.
+    EmptyInput,
+    /// The charset of a non UTF-8 byte stream could not be guessed.
+    CharsetNotDetected,
+    /// The byte stream could not be decoded with the detected charset.
+    DecodeFailure(String),
+    /// Neither a title nor a text could be extracted from the document.
+    NoContentFound,
+    /// The URL of the page could not be parsed.
+    InvalidUrl(String),
+}
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExtractionError::EmptyInput => write!(f, "empty input"),
+            ExtractionError::CharsetNotDetected => write!(f, "charset could not be detected"),
+            ExtractionError::DecodeFailure(cause) => write!(f, "decoding failed: {}", cause),
+            ExtractionError::NoContentFound => write!(f, "no content found"),
+            ExtractionError::InvalidUrl(url) => write!(f, "invalid URL: {}", url),
+        }
+    }
+}
+
+impl Error for ExtractionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(ExtractionError::EmptyInput.to_string(), "empty input");
+        assert_eq!(ExtractionError::DecodeFailure(String::from("invalid sequence")).to_string(), "decoding failed: invalid sequence");
+    }
+}
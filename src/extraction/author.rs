@@ -0,0 +1,86 @@
+use regex::Regex;
+
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+    static ref BY_PREFIX_REGEX: Regex = Regex::new(r"(?i)^by\b[:\s]*").unwrap();
+    static ref AUTHOR_SEPARATOR_REGEX: Regex = Regex::new(r"(?i)\s+and\s+|\s*;\s*").unwrap();
+}
+
+const MAX_AUTHOR_WORDS: usize = 6;
+
+/// Cleans up raw bylines: strips the "By " prefix, splits "A and B" and drops duplicates, URLs and
+/// sentences that cannot be a name. "A & B" is only split when both sides are full names, since
+/// firms and desks are named that way too.
+pub fn normalize_authors(raw_authors: &[String]) -> Vec<String> {
+    let mut authors: Vec<String> = Vec::new();
+    for raw_author in raw_authors {
+        if is_url(raw_author.trim()) {
+            continue;
+        }
+        let collapsed = SPACES_REGEX.replace_all(raw_author.trim(), " ");
+        let without_prefix = BY_PREFIX_REGEX.replace(&collapsed, "");
+        for part in AUTHOR_SEPARATOR_REGEX.split(&without_prefix).flat_map(split_full_names) {
+            let name = part.trim_matches(|c: char| c.is_whitespace() || c == ',' || c == '|');
+            if is_author_name(name) && !authors.iter().any(|a| a.to_lowercase() == name.to_lowercase()) {
+                authors.push(String::from(name));
+            }
+        }
+    }
+    authors
+}
+
+/// Splits "Jane Doe & John Smith" but neither "Smith & Jones" nor "Procter & Gamble".
+fn split_full_names(part: &str) -> Vec<&str> {
+    let names: Vec<&str> = part.split('&').collect();
+    if names.len() > 1 && names.iter().all(|name| name.split_whitespace().count() >= 2) {
+        names
+    } else {
+        vec![part]
+    }
+}
+
+fn is_url(value: &str) -> bool {
+    value.contains("://") || value.starts_with("www.")
+}
+
+fn is_author_name(name: &str) -> bool {
+    !name.is_empty()
+        && !is_url(name)
+        && name.split_whitespace().count() <= MAX_AUTHOR_WORDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(raw_authors: &[&str]) -> Vec<String> {
+        let raw_authors: Vec<String> = raw_authors.iter().map(|a| String::from(*a)).collect();
+        normalize_authors(&raw_authors)
+    }
+
+    #[test]
+    fn test_normalize_authors_by_prefix() {
+        assert_eq!(normalize(&["By  John Smith "]), vec!["John Smith"]);
+        assert_eq!(normalize(&["by: Jane Doe"]), vec!["Jane Doe"]);
+        assert_eq!(normalize(&["Byron Wallace"]), vec!["Byron Wallace"]);
+    }
+
+    #[test]
+    fn test_normalize_authors_split() {
+        assert_eq!(normalize(&["Gregor Aisch and Josh Keller"]), vec!["Gregor Aisch", "Josh Keller"]);
+        assert_eq!(normalize(&["By Jane Doe & John Smith"]), vec!["Jane Doe", "John Smith"]);
+        assert_eq!(normalize(&["By Smith & Jones"]), vec!["Smith & Jones"]);
+        assert_eq!(normalize(&["Jane Doe & Procter"]), vec!["Jane Doe & Procter"]);
+    }
+
+    #[test]
+    fn test_normalize_authors_dedupe() {
+        assert_eq!(normalize(&["Josh Keller", "JOSH KELLER", "By Josh Keller"]), vec!["Josh Keller"]);
+    }
+
+    #[test]
+    fn test_normalize_authors_filtered() {
+        assert!(normalize(&["", "http://www.facebook.com/frederic", "This is a whole sentence rather than a name"]).is_empty());
+        assert!(normalize(&["http://www.dailymail.co.uk/home/search.html?s=&authornamef=Mail+Online+Reporter"]).is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeSet;
+
+use regex::Regex;
+use select::node::Node;
+use select::predicate::{Name, Predicate};
+
+use crate::configuration::BoilerplatePatterns;
+
+const PROTECTED_TAGS: [&str; 4] = ["html", "body", "main", "article"];
+
+/// Finds, among the given nodes, the ones matching the boilerplate patterns and returns their
+/// indexes along with the ones of their descendants.
+pub fn get_boilerplate_nodes<'a, I: Iterator<Item = Node<'a>>>(nodes: I, patterns: &BoilerplatePatterns) -> BTreeSet<usize> {
+    let mut boilerplate_nodes: BTreeSet<usize> = BTreeSet::new();
+    if patterns.is_empty() {
+        return boilerplate_nodes;
+    }
+    let class_regex = get_class_regex(patterns);
+    for node in nodes {
+        if boilerplate_nodes.contains(&node.index()) || !is_boilerplate(&node, patterns, class_regex.as_ref()) {
+            continue;
+        }
+        boilerplate_nodes.insert(node.index());
+        boilerplate_nodes.extend(node.descendants().map(|descendant| descendant.index()));
+    }
+    boilerplate_nodes
+}
+
+/// Builds a regex matching the words of a `class` or `id` attribute starting with a pattern.
+fn get_class_regex(patterns: &BoilerplatePatterns) -> Option<Regex> {
+    if patterns.class_patterns.is_empty() {
+        return None;
+    }
+    let alternatives: Vec<String> = patterns.class_patterns.iter().map(|pattern| regex::escape(pattern)).collect();
+    Regex::new(&format!(r"(?i)(?:^|[^\p{{L}}\p{{N}}])(?:{})", alternatives.join("|"))).ok()
+}
+
+fn is_boilerplate(node: &Node, patterns: &BoilerplatePatterns, class_regex: Option<&Regex>) -> bool {
+    let name = match node.name() {
+        Some(name) if !PROTECTED_TAGS.contains(&name) => name,
+        _ => return false
+    };
+    let matches_tag = patterns.tags.contains(name);
+    let matches_role = node.attr("role").is_some_and(|role| role.split_whitespace().any(|role| patterns.roles.contains(&role.to_lowercase())));
+    let matches_class = class_regex.is_some_and(|regex| {
+        node.attr("class").is_some_and(|class| regex.is_match(class)) || node.attr("id").is_some_and(|id| regex.is_match(id))
+    });
+    (matches_tag || matches_role || matches_class) && node.find(Name("article").or(Name("main"))).next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use select::document::Document;
+    use select::predicate::Any;
+
+    use super::*;
+
+    fn get_boilerplate_texts(html: &str, patterns: &BoilerplatePatterns) -> Vec<String> {
+        let document = Document::from(html);
+        let boilerplate_nodes = get_boilerplate_nodes(document.find(Any), patterns);
+        document.find(Name("p"))
+            .filter(|p| boilerplate_nodes.contains(&p.index()))
+            .map(|p| p.text())
+            .collect()
+    }
+
+    #[test]
+    fn test_get_boilerplate_nodes() {
+        let html = r#"<html><body class="has-sidebar"><div class="page-with-sidebar"><article><p>Text</p>
+            <div class="social-share"><p>Share</p></div>
+            <div id="Comments"><p>Comment</p></div>
+            <div class="timeshare"><p>Timeshare</p></div>
+            <div role="complementary"><p>Complementary</p></div>
+            <aside><p>Aside</p></aside>
+            </article><footer><p>Footer</p></footer></div></body></html>"#;
+        let texts = get_boilerplate_texts(html, &BoilerplatePatterns::default());
+        assert_eq!(texts, vec!["Share", "Comment", "Complementary", "Aside", "Footer"]);
+    }
+
+    #[test]
+    fn test_get_boilerplate_nodes_configured() {
+        let html = r#"<html><body><div class="share"><p>Share</p></div><div class="ad-slot"><p>Ad</p></div></body></html>"#;
+        assert!(get_boilerplate_texts(html, &BoilerplatePatterns::none()).is_empty());
+        let mut patterns = BoilerplatePatterns::none();
+        patterns.class_patterns.insert(String::from("ad-"));
+        assert_eq!(get_boilerplate_texts(html, &patterns), vec!["Ad"]);
+    }
+}
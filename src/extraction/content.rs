@@ -1,16 +1,82 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::vec::Vec;
 
 use select::document::Document;
-use select::predicate::{Name, Predicate, Text};
-use unicode_segmentation::UnicodeSegmentation;
+use regex::Regex;
+use select::predicate::{Any, Name, Predicate, Text};
+use url::Url;
 
+use crate::article::{Block, BLOCK_SEPARATOR, BlockKind, Link};
+use crate::configuration::{BoilerplatePatterns, TextNormalization};
+use crate::extraction::boilerplate::get_boilerplate_nodes;
+use crate::extraction::normalization::{normalize_block, normalize_text};
 use crate::extraction::predicate::ImageWithLink;
+use crate::extraction::resolver::filter_url;
+use crate::extraction::segmentation::get_words;
 use crate::extraction::stopwords::{count_stopwords, has_more_stopwords_than};
 
 use super::select::node::Node;
 
-pub fn get_top_node<'a>(document: &'a Document, lang: &'a str) -> Option<Node<'a>> {
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+const CHARS_PER_DENSITY_POINT: usize = 10;
+
+/// How the paragraphs are weighted when looking for the top node. Without a stopword list for the
+/// language, a paragraph is weighted by the length of its text and the number of its punctuation
+/// marks, which works for the languages without spaces between words as well.
+#[derive(Clone, Copy)]
+enum Scoring<'a> {
+    Stopwords(&'a [&'a str]),
+    TextDensity,
+}
+
+impl<'a> Scoring<'a> {
+    fn for_stopwords(stopwords: &'a [&'a str]) -> Self {
+        if stopwords.is_empty() { Scoring::TextDensity } else { Scoring::Stopwords(stopwords) }
+    }
+
+    fn score(self, text: &str) -> usize {
+        match self {
+            Scoring::Stopwords(stopwords) => count_stopwords(text, stopwords),
+            Scoring::TextDensity => get_text_density_score(text),
+        }
+    }
+
+    fn has_more_than(self, text: &str, n: usize) -> bool {
+        match self {
+            Scoring::Stopwords(stopwords) => has_more_stopwords_than(text, stopwords, n),
+            Scoring::TextDensity => get_text_density_score(text) >= n,
+        }
+    }
+}
+
+fn is_sentence_punctuation(c: char) -> bool {
+    matches!(c, ',' | '.' | ';' | ':' | '!' | '?' | '、' | '。' | '，' | '；' | '：' | '！' | '？' | '،' | '؛' | '؟' | '।')
+}
+
+fn get_text_density_score(text: &str) -> usize {
+    let mut nb_chars: usize = 0;
+    let mut nb_punctuation: usize = 0;
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        nb_chars += 1;
+        if is_sentence_punctuation(c) {
+            nb_punctuation += 1;
+        }
+    }
+    if nb_punctuation == 0 {
+        return 0;
+    }
+    nb_punctuation + nb_chars / CHARS_PER_DENSITY_POINT
+}
+
+/// Finds the node holding the main content. The stopwords must be sorted; without stopwords the
+/// paragraphs are weighted by their text density. The paragraphs inside boilerplate, such as
+/// comments or sidebars, are not scored.
+pub fn get_top_node<'a>(document: &'a Document, stopwords: &[&str], patterns: &BoilerplatePatterns) -> Option<Node<'a>> {
+    let scoring = Scoring::for_stopwords(stopwords);
+    let boilerplate_nodes = get_boilerplate_nodes(document.find(Any), patterns);
     let mut top_node: Option<usize> = None;
     let starting_boost: f32 = 1.0;
     let mut i: usize = 0;
@@ -18,14 +84,17 @@ pub fn get_top_node<'a>(document: &'a Document, lang: &'a str) -> Option<Node<'a
     let mut score_per_node: BTreeMap<usize, usize> = BTreeMap::new();
     let mut nodes_with_text_count: usize = 0;
     for node in document.find(Name("p").or(Name("pre")).or(Name("td"))) {
+        if boilerplate_nodes.contains(&node.index()) {
+            continue;
+        }
         let node_text = node.text();
         let text_words_count = count_words(&node_text);
-        if has_more_stopwords_than(&node_text, lang, 2) && !is_high_density_link(&node, text_words_count) {
-            stopwords_per_node.insert(node.index(), count_stopwords(&node_text, &lang));
+        if scoring.has_more_than(&node_text, 2) && !is_high_density_link(&node, text_words_count) {
+            stopwords_per_node.insert(node.index(), scoring.score(&node_text));
             nodes_with_text_count += 1;
         }
         let bottom_negative_scoring = nodes_with_text_count / 4;
-        let mut boost_score = if is_boostable(&node, lang) { 50.0 / starting_boost } else { 0.0 };
+        let mut boost_score = if is_boostable(&node, scoring) { 50.0 / starting_boost } else { 0.0 };
         if nodes_with_text_count > 15 {
             let booster: i32 = (bottom_negative_scoring + i - nodes_with_text_count) as i32;
             if booster >= 0 {
@@ -33,13 +102,13 @@ pub fn get_top_node<'a>(document: &'a Document, lang: &'a str) -> Option<Node<'a
                 if x > 40 {
                     boost_score = 5.0;
                 } else {
-                    boost_score = -1.0 * (x) as f32;
+                    boost_score = -(x as f32);
                 }
             }
         }
         for (node_index, stopwords) in stopwords_per_node.iter() {
             let up_score = stopwords + (boost_score) as usize;
-            let parent_node = document.nth(*node_index).unwrap().parent().unwrap();
+            let parent_node = get_scoring_parent(&document.nth(*node_index).unwrap()).unwrap();
 
             *score_per_node.entry(parent_node.index()).or_insert(0) += up_score;
 
@@ -63,13 +132,33 @@ pub fn get_top_node<'a>(document: &'a Document, lang: &'a str) -> Option<Node<'a
     }
 }
 
-fn is_boostable(node: &Node, lang: &str) -> bool {
+const TABLE_STRUCTURE_TAGS: [&str; 5] = ["tr", "tbody", "thead", "tfoot", "table"];
+
+/// A cell holding a single paragraph, such as the caption cells of the galleries laid out with
+/// one table per picture, is not a container of its own: the paragraph is scored along with the
+/// siblings of its table.
+fn get_scoring_parent<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let parent = node.parent()?;
+    let is_single_paragraph_cell = matches!(parent.name(), Some("td") | Some("th"))
+        && node.is(Name("p"))
+        && parent.find(Name("p")).nth(1).is_none();
+    if !is_single_paragraph_cell {
+        return Some(parent);
+    }
+    let mut ancestor = parent.parent();
+    while let Some(table_node) = ancestor.filter(|a| a.name().is_some_and(|name| TABLE_STRUCTURE_TAGS.contains(&name))) {
+        ancestor = table_node.parent();
+    }
+    ancestor.or(Some(parent))
+}
+
+fn is_boostable(node: &Node, scoring: Scoring) -> bool {
     let mut sibling_distance: u8 = 0;
     while let Some(sibling) = node.next() {
         if sibling_distance < 3 {
             if let Some("p") = sibling.name() {
                 let sibling_text = sibling.text();
-                if has_more_stopwords_than(&sibling_text, lang, 5) {
+                if scoring.has_more_than(&sibling_text, 5) {
                     return true;
                 }
             }
@@ -98,47 +187,204 @@ fn is_high_density_link(node: &Node, text_words_count: usize) -> bool {
 
 #[inline]
 fn count_words(text: &str) -> usize {
-    text.unicode_words().count()
+    get_words(text, &[]).len()
+}
+
+/// Splits the content of the top node into blocks, leaving out the removed nodes, and collects its
+/// links with their offsets in the text joining the blocks.
+pub fn get_cleaned_body_and_links(node: Node, excluded_nodes: &HashSet<usize>, normalization: &TextNormalization) -> (Vec<Block>, Vec<Link>) {
+    let mut builder = BodyBuilder {
+        excluded_nodes,
+        normalization,
+        blocks: Vec::new(),
+        links: Vec::new(),
+        text_length: 0,
+        kind: BlockKind::Paragraph,
+        text: String::with_capacity(200),
+        block_links: Vec::new(),
+    };
+    builder.visit(node, None);
+    builder.flush();
+    (builder.blocks, builder.links)
 }
 
-pub fn get_cleaned_text_and_links(node: Node, _lang: &str) -> (String, Vec<String>) {
-    let excluded_nodes = get_removed_nodes(node);
+fn get_block_kind(node: &Node) -> Option<BlockKind> {
+    match node.name()? {
+        "h1" => Some(BlockKind::Heading(1)),
+        "h2" => Some(BlockKind::Heading(2)),
+        "h3" => Some(BlockKind::Heading(3)),
+        "h4" => Some(BlockKind::Heading(4)),
+        "h5" => Some(BlockKind::Heading(5)),
+        "h6" => Some(BlockKind::Heading(6)),
+        "p" => Some(BlockKind::Paragraph),
+        "li" => Some(BlockKind::ListItem),
+        "blockquote" => Some(BlockKind::Quote),
+        "pre" => Some(BlockKind::Code),
+        // A table holding paragraphs is a layout table.
+        "table" if node.find(Name("p")).next().is_none() => Some(BlockKind::Table),
+        "figure" => Some(BlockKind::Figure),
+        _ => None
+    }
+}
 
-    let mut text = String::with_capacity(200);
-    let mut links: Vec<String> = Vec::new();
-    let p_predicate = Name("p");
-    let a_predicate = ImageWithLink();
-    node.descendants()
-        .filter(|n| !excluded_nodes.contains(&n.index()))
-        .for_each(|descendant| {
-            if descendant.children().count() == 0 {
-                text.push_str(descendant.text().as_str());
+struct BodyBuilder<'a> {
+    excluded_nodes: &'a HashSet<usize>,
+    normalization: &'a TextNormalization,
+    blocks: Vec<Block>,
+    links: Vec<Link>,
+    /// Length, in characters, of the text joining the blocks built so far.
+    text_length: usize,
+    kind: BlockKind,
+    text: String,
+    /// Links of the current block, with their offsets in its text before the block normalization.
+    block_links: Vec<Link>,
+}
 
-                if descendant.is(p_predicate) {
-                    text.push('\n');
+impl<'a> BodyBuilder<'a> {
+    /// Visits a node inside a block element of the given kind. The paragraphs of a quote or a list
+    /// item take its kind, a code block or a table is never split, and only the caption of a figure
+    /// is kept.
+    fn visit(&mut self, node: Node, container: Option<BlockKind>) {
+        if self.excluded_nodes.contains(&node.index()) {
+            return;
+        }
+        if let Some(text) = node.as_text() {
+            let keep_whitespace = container == Some(BlockKind::Code);
+            let text = normalize_text(text, self.normalization, keep_whitespace);
+            let follows_space = self.text.is_empty() || self.text.ends_with([' ', '\t', '\n']);
+            if self.normalization.collapse_whitespace && !keep_whitespace && follows_space {
+                self.text.push_str(text.trim_start_matches(' '));
+            } else {
+                self.text.push_str(&text);
+            }
+            return;
+        }
+        if let Some("script") | Some("style") | Some("noscript") | Some("template") = node.name() {
+            return;
+        }
+        if node.is(ImageWithLink()) {
+            if let Some(mut link) = get_link(&node, self.text.chars().count()) {
+                link.text = String::from(normalize_text(&link.text, self.normalization, false).trim());
+                self.block_links.push(link);
+            }
+        }
+        match (container, get_block_kind(&node)) {
+            (Some(BlockKind::Code), _) | (Some(BlockKind::Table), _) | (_, None) => {
+                if let Some("br") = node.name() {
+                    self.text.push('\n');
+                }
+                for child in node.children() {
+                    self.visit(child, container);
+                }
+                match node.name() {
+                    Some("td") | Some("th") if container == Some(BlockKind::Table) => {
+                        let cell_length = self.text.trim_end_matches(' ').len();
+                        self.text.truncate(cell_length);
+                        self.text.push('\t');
+                    }
+                    Some("tr") if container == Some(BlockKind::Table) => {
+                        let row_length = self.text.trim_end_matches('\t').len();
+                        self.text.truncate(row_length);
+                        self.text.push('\n');
+                    }
+                    _ => ()
                 }
             }
-
-            for l in descendant.find(a_predicate) {
-                links.push(String::from(l.attr("href").unwrap()));
+            (_, Some(kind)) => {
+                let kind = match (container, kind) {
+                    (Some(container), BlockKind::Paragraph) => container,
+                    _ => kind
+                };
+                self.flush();
+                self.kind = kind;
+                if kind == BlockKind::Figure {
+                    for caption in node.find(Name("figcaption")) {
+                        self.visit(caption, Some(kind));
+                    }
+                } else {
+                    for child in node.children() {
+                        self.visit(child, Some(kind));
+                    }
+                }
+                self.flush();
+                self.kind = container.unwrap_or(BlockKind::Paragraph);
             }
-        });
+        }
+    }
+
+    /// Ends the current block. A block without text is dropped, and its links point to the end
+    /// of the text.
+    fn flush(&mut self) {
+        let (block_text, offsets) = normalize_block(&self.text, self.normalization, self.kind == BlockKind::Code);
+        let block_length = block_text.chars().count();
+        let block_start = if self.blocks.is_empty() { 0 } else { self.text_length + BLOCK_SEPARATOR.chars().count() };
+        for mut link in self.block_links.drain(..) {
+            link.offset = if block_length == 0 {
+                self.text_length
+            } else {
+                block_start + offsets[link.offset.min(offsets.len() - 1)]
+            };
+            self.links.push(link);
+        }
+        if block_length > 0 {
+            self.blocks.push(Block { kind: self.kind, text: block_text });
+            self.text_length = block_start + block_length;
+        }
+        self.text.clear();
+    }
+}
 
-    (text, links)
+/// Builds the link of an anchor, unless its URL is neither relative nor an `http` or `https` one,
+/// so that the links are the same whether or not they are resolved later on.
+fn get_link(node: &Node, offset: usize) -> Option<Link> {
+    Some(Link {
+        href: filter_url(node.attr("href").unwrap_or_default())?,
+        text: String::from(SPACES_REGEX.replace_all(node.text().trim(), " ")),
+        rel: node.attr("rel").unwrap_or_default()
+            .split_whitespace()
+            .map(|rel| rel.to_lowercase())
+            .collect(),
+        title: String::from(node.attr("title").unwrap_or_default().trim()),
+        is_external: false,
+        offset,
+    })
 }
 
-fn get_removed_nodes(node: Node) -> Vec<usize> {
-    let mut removed_nodes: Vec<usize> = Vec::with_capacity(100);
+/// Flags the links pointing to another host than the one of the reference URL, the `www.` prefix
+/// aside. Relative links are internal, absolute ones are external when there is no reference host.
+pub fn mark_external_links(links: &mut [Link], reference_url: &str) {
+    let reference_host = Url::parse(reference_url).ok()
+        .and_then(|url| url.host_str().map(|host| String::from(host.trim_start_matches("www."))));
+    for link in links.iter_mut() {
+        link.is_external = match Url::parse(&link.href) {
+            Ok(url) => match (url.host_str(), &reference_host) {
+                (Some(host), Some(reference_host)) => host.trim_start_matches("www.") != reference_host,
+                (Some(_), None) => true,
+                _ => false
+            },
+            _ => false
+        };
+    }
+}
+
+/// Lists the nodes of the top node left out of the text: the children which are neither
+/// paragraphs nor content blocks, and the boilerplate.
+pub fn get_removed_nodes(node: Node, patterns: &BoilerplatePatterns) -> HashSet<usize> {
+    let mut removed_nodes: HashSet<usize> = HashSet::with_capacity(100);
     let p_tag_predicate = Name("p");
     let td_tag_predicate = Name("td");
     node.children()
         .filter(|child| !child.is(p_tag_predicate))
         .for_each(|child| {
             let child_text = child.text();
-            if !is_high_density_link(&child, count_words(&child_text)) {
-                removed_nodes.push(child.index());
+            let child_words_count = count_words(&child_text);
+            if is_content_block(&child) && child_words_count > 0 && !is_high_density_link(&child, child_words_count) {
+                return;
+            }
+            if !is_high_density_link(&child, child_words_count) {
+                removed_nodes.insert(child.index());
                 for descendant in child.descendants() {
-                    removed_nodes.push(descendant.index());
+                    removed_nodes.insert(descendant.index());
                 }
             } else {
                 let sub_paragraphes = child.find(p_tag_predicate);
@@ -155,9 +401,23 @@ fn get_removed_nodes(node: Node) -> Vec<usize> {
                 }
             }
         });
+    removed_nodes.extend(get_boilerplate_nodes(node.descendants(), patterns));
     removed_nodes
 }
 
+/// Tells whether a child of the top node is a heading, a list, a quote, a code block, a table, a
+/// figure or a layout table holding paragraphs, which are kept along the paragraphs unless they
+/// are mostly made of links.
+fn is_content_block(node: &Node) -> bool {
+    match node.name() {
+        Some("ul") | Some("ol") => true,
+        Some(name) if (name == "td" || name == "th" || TABLE_STRUCTURE_TAGS.contains(&name))
+            && node.find(Name("p")).next().is_some() => true,
+        Some(_) => get_block_kind(node).is_some(),
+        _ => false
+    }
+}
+
 fn get_index_and_descendant_indexes(child: Node) -> Vec<usize> {
     let descendants = child.descendants();
     let (size, _) = descendants.size_hint();
@@ -174,35 +434,39 @@ fn get_index_and_descendant_indexes(child: Node) -> Vec<usize> {
 mod tests {
     use select::document::Document;
 
+    use crate::article::get_blocks_text;
+    use crate::configuration::{BoilerplatePatterns, Configuration, StopwordsMode, TextNormalization};
+    use crate::extraction::stopwords::{get_stopwords, get_stopwords_from_language};
+
     use super::*;
 
     #[test]
     fn test_get_cleaned_text_and_links() {
         let document = Document::from(include_str!("sites/theguardian.com.html"));
-        let option = get_top_node(&document, "en").unwrap();
-        let (text, _links) = get_cleaned_text_and_links(option, "en");
-        println!("{}", text);
+        let option = get_top_node(&document, &get_stopwords_from_language("en"), &BoilerplatePatterns::default()).unwrap();
+        let (body, _links) = get_cleaned_body_and_links(option, &get_removed_nodes(option, &BoilerplatePatterns::default()), &TextNormalization::default());
+        println!("{}", get_blocks_text(&body));
     }
 
     #[test]
     fn test_get_cleaned_text_and_links_techcrunch() {
         let document = Document::from(include_str!("sites/techcrunch.com.html"));
-        let option = get_top_node(&document, "en").unwrap();
-        let (text, _links) = get_cleaned_text_and_links(option, "en");
-        println!("{}", text);
+        let option = get_top_node(&document, &get_stopwords_from_language("en"), &BoilerplatePatterns::default()).unwrap();
+        let (body, _links) = get_cleaned_body_and_links(option, &get_removed_nodes(option, &BoilerplatePatterns::default()), &TextNormalization::default());
+        println!("{}", get_blocks_text(&body));
     }
 
     #[test]
     fn test_get_top_node_simple() {
         let document = Document::from("<html><body><div><p>This is a paragraph</p><h1></h1><br/><pre>Paris</pre></div><span></span></html>");
-        assert_eq!(get_top_node(&document, "en").unwrap().name().unwrap(), "div");
+        assert_eq!(get_top_node(&document, &get_stopwords_from_language("en"), &BoilerplatePatterns::default()).unwrap().name().unwrap(), "div");
     }
 
     #[test]
     fn test_removed_nodes() {
         let document = Document::from(include_str!("sites/theguardian.com.html"));
-        let node = get_top_node(&document, "en").unwrap();
-        let removed_nodes = get_removed_nodes(node);
+        let node = get_top_node(&document, &get_stopwords_from_language("en"), &BoilerplatePatterns::default()).unwrap();
+        let removed_nodes = get_removed_nodes(node, &BoilerplatePatterns::default());
         for i in removed_nodes.iter() {
             println!("Removed node : {}", document.nth(*i).unwrap().text());
         }
@@ -211,20 +475,244 @@ mod tests {
     #[test]
     fn test_get_top_node_nominal() {
         let document = Document::from(include_str!("sites/theguardian.com.html"));
-        let node = get_top_node(&document, "en").unwrap();
+        let node = get_top_node(&document, &get_stopwords_from_language("en"), &BoilerplatePatterns::default()).unwrap();
         assert_eq!(node.name().unwrap(), "div");
 //println!("{}", node.text());
     }
 
+    #[test]
+    fn test_get_top_node_text_density_huffingtonpost_jp() {
+        // The entry of this page has expired, and its only Japanese prose is the slideshow promoted in
+        // the footer of the article, which the default patterns leave out.
+        let document = Document::from(include_str!("sites/huffingtonpost.jp.html"));
+        let node = get_top_node(&document, &get_stopwords_from_language("ja"), &BoilerplatePatterns::none()).unwrap();
+        assert!(node.text().contains("深海の熱水噴出孔の周辺に生息する特異な生物"));
+    }
+
+    #[test]
+    fn test_get_top_node_boilerplate_huffingtonpost_jp() {
+        // The slideshow is a promotion held by the footer of the article.
+        let document = Document::from(include_str!("sites/huffingtonpost.jp.html"));
+        let node = get_top_node(&document, &get_stopwords_from_language("ja"), &BoilerplatePatterns::default()).unwrap();
+        assert!(!node.text().contains("深海の熱水噴出孔の周辺に生息する特異な生物"));
+    }
+
+    #[test]
+    fn test_get_top_node_boilerplate_dailymail() {
+        let document = Document::from(include_str!("sites/dailymail.co.uk.html"));
+        let patterns = BoilerplatePatterns::default();
+        let node = get_top_node(&document, &get_stopwords_from_language("en"), &patterns).unwrap();
+        let (body, _) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &patterns), &TextNormalization::default());
+        let text = get_blocks_text(&body);
+        assert!(text.contains("Major high street stores have been accused of ripping off shoppers"));
+        assert!(text.ends_with("MailOnline has contacted Debenhams and House of Fraser for comment."));
+        assert!(!text.contains("The comments below have not been moderated."));
+        assert!(!text.contains("Copy link to paste in your message"));
+        assert!(!text.contains("Published by Associated Newspapers Ltd"));
+    }
+
+    #[test]
+    fn test_get_top_node_boilerplate_foxnews() {
+        let document = Document::from(include_str!("sites/foxnews.com.html"));
+        let patterns = BoilerplatePatterns::default();
+        let node = get_top_node(&document, &get_stopwords_from_language("en"), &patterns).unwrap();
+        let (body, _) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &patterns), &TextNormalization::default());
+        let text = get_blocks_text(&body);
+        assert!(text.starts_with("Hillary Rodham Clinton has locked up public support from half of the Democratic insiders"));
+        assert!(text.ends_with("said California Democratic Rep. Tony Cardenas."));
+        assert!(!text.contains("FOX News Network, LLC. All rights reserved."));
+    }
+
+    #[test]
+    fn test_get_cleaned_body_and_links_boilerplate() {
+        let document = Document::from(r#"<html><body><div>
+            <p>This is the first paragraph of the article, long enough to be kept in the text.</p>
+            <div class="social-share"><p>Share this article with your friends on every network.</p></div>
+            <ul class="related-stories"><li>A related story with a long enough title</li></ul>
+            <p>This is the second paragraph of the article, long enough to be kept as well.</p>
+            </div></body></html>"#);
+        let node = document.find(Name("div")).next().unwrap();
+        let (body, _) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), &TextNormalization::default());
+        assert_eq!(body.len(), 2);
+        let (body, _) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::none()), &TextNormalization::default());
+        assert_eq!(body.len(), 3);
+    }
+
+    #[test]
+    fn test_get_top_node_text_density_vnexpress() {
+        let document = Document::from(include_str!("sites/vnexpress.net.html"));
+        let node = get_top_node(&document, &get_stopwords_from_language("vi"), &BoilerplatePatterns::default()).unwrap();
+        assert_eq!(node.attr("class"), Some("fck_detail width_common"));
+        let (body, _) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), &TextNormalization::default());
+        assert_eq!(body.len(), 8);
+        assert!(body[1].text.contains("Tôi chọn hoa hồng màu vàng để tặng anh Sơn"));
+    }
+
+    #[test]
+    fn test_get_text_density_score() {
+        assert_eq!(get_text_density_score("No punctuation here"), 0);
+        assert_eq!(get_text_density_score("安倍晋三首相は12日、インドのモディ首相と会談した。"), 4);
+    }
+
     #[test]
     fn test_has_more_stopwords_than() {
         let text = String::from("I live in London in England");
-        assert!(has_more_stopwords_than(&text, "en", 2));
+        assert!(has_more_stopwords_than(&text, &get_stopwords_from_language("en"), 2));
     }
 
     #[test]
     fn test_count_stopwords() {
         let text = String::from("I live in London in England");
-        assert_eq!(count_stopwords(&text, "en"), 3);
+        assert_eq!(count_stopwords(&text, &get_stopwords_from_language("en")), 3);
+    }
+
+    #[test]
+    fn test_count_stopwords_languages() {
+        assert_eq!(count_stopwords("Jag bor i Stockholm och jag arbetar där", &get_stopwords_from_language("sv")), 5);
+        assert_eq!(count_stopwords("Jag bor i Stockholm och jag arbetar där", &get_stopwords_from_language("sw")), 0);
+        assert_eq!(count_stopwords("Mieszkam w Warszawie i pracuję tam", &get_stopwords_from_language("pl")), 3);
+        assert_eq!(count_stopwords("Eu moro no Rio e trabalho lá", &get_stopwords_from_language("pt-BR")), 3);
+        assert_eq!(count_stopwords("Το σπίτι και ο κήπος", &get_stopwords_from_language("el")), 3);
+    }
+
+    #[test]
+    fn test_count_stopwords_unspaced() {
+        assert_eq!(count_stopwords("これは日本語の文章です", &get_stopwords_from_language("ja")), 4);
+        // The kana of an inflection, such as the し and い of 美しい, are not words of their own.
+        assert_eq!(count_stopwords("美しい写真が少ない", &get_stopwords_from_language("ja")), 2);
+        assert_eq!(count_stopwords("我们在北京的大学学习", &get_stopwords_from_language("zh")), 3);
+        assert_eq!(count_stopwords("แมวและสุนัขอยู่ในบ้าน", &get_stopwords_from_language("th")), 3);
+    }
+
+    #[test]
+    fn test_count_stopwords_custom() {
+        let mut configuration = Configuration::default();
+        configuration.add_stopwords("vi", &["tôi", "và", "ở"], StopwordsMode::Merge);
+        configuration.add_stopwords("en-GB", &["london"], StopwordsMode::Merge);
+        configuration.add_stopwords("de", &["berlin"], StopwordsMode::Replace);
+        let text = "Tôi sống ở Hà Nội và tôi làm việc ở đó";
+        assert_eq!(count_stopwords(text, &get_stopwords("vi", &configuration.custom_stopwords)), 5);
+        let text = "I live in London in England";
+        assert_eq!(count_stopwords(text, &get_stopwords("en", &configuration.custom_stopwords)), 4);
+        let text = "Ich wohne in Berlin und arbeite dort";
+        assert_eq!(count_stopwords(text, &get_stopwords_from_language("de")), 4);
+        assert_eq!(count_stopwords(text, &get_stopwords("de", &configuration.custom_stopwords)), 1);
+    }
+
+    #[test]
+    fn test_count_words_unspaced() {
+        assert_eq!(count_words("東京大学の研究者"), 4);
+        assert_eq!(count_words("Rust は 2015年に"), 4);
+    }
+
+    #[test]
+    fn test_get_cleaned_text_and_links_model() {
+        let document = Document::from(r#"<html><body><div>
+            <p>This is a paragraph with <a href="http://other.com/page" rel="nofollow UGC" title=" Other ">an   external link</a> in it.</p>
+            <p>The second one has <span><a href="/internal">an internal link</a></span> and it is in the text.</p>
+            </div></body></html>"#);
+        let node = get_top_node(&document, &get_stopwords_from_language("en"), &BoilerplatePatterns::default()).unwrap();
+        let (body, links) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), &TextNormalization::default());
+        let text = get_blocks_text(&body);
+        assert_eq!(links.len(), 2);
+        let link = &links[0];
+        assert_eq!(link.href, "http://other.com/page");
+        assert_eq!(link.text, "an external link");
+        assert_eq!(link.rel, vec!["nofollow", "ugc"]);
+        assert_eq!(link.title, "Other");
+        let offset_text: String = text.chars().skip(links[1].offset).take(16).collect();
+        assert_eq!(offset_text, "an internal link");
+    }
+
+    #[test]
+    fn test_get_cleaned_text_and_links_filtered() {
+        let document = Document::from(r#"<html><body><div>
+            <p>This paragraph has <a href="javascript:void(0)">a script</a>, <a href="mailto:me@example.com">a mail</a>,
+            <a href="java&#x09;script:alert(1)">a split script</a> and <a href="/internal">an internal link</a>.</p>
+            </div></body></html>"#);
+        let node = document.find(Name("div")).next().unwrap();
+        let (body, links) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), &TextNormalization::default());
+        assert_eq!(get_blocks_text(&body), "This paragraph has a script, a mail, a split script and an internal link.");
+        assert_eq!(links.iter().map(|link| link.href.as_str()).collect::<Vec<&str>>(), vec!["/internal"]);
+    }
+
+    #[test]
+    fn test_get_cleaned_body_and_links_blocks() {
+        let document = Document::from(r#"<html><body><div>
+            <h2>  A heading </h2>
+            <p>The first paragraph of the article, with <a href="/link">a link</a> in it.</p>
+            <blockquote><p>A quoted paragraph.</p><p>Another one.</p></blockquote>
+            <ul><li>First item</li><li><a href="/item">Second</a> item</li></ul>
+            <pre>
+fn main() {
+    run();
+}</pre>
+            <table><tr><th>Name</th><th>Value</th></tr><tr><td>a</td><td>1</td></tr></table>
+            <figure><img src="a.jpg"><span>Share</span><figcaption>A caption</figcaption></figure>
+            <p>A line<br>and another one.</p>
+            <script>var x = 1;</script>
+            </div></body></html>"#);
+        let node = document.find(Name("div")).next().unwrap();
+        let (body, links) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), &TextNormalization::default());
+        let blocks: Vec<(BlockKind, &str)> = body.iter().map(|block| (block.kind, block.text.as_str())).collect();
+        assert_eq!(blocks, vec![
+            (BlockKind::Heading(2), "A heading"),
+            (BlockKind::Paragraph, "The first paragraph of the article, with a link in it."),
+            (BlockKind::Quote, "A quoted paragraph."),
+            (BlockKind::Quote, "Another one."),
+            (BlockKind::ListItem, "First item"),
+            (BlockKind::ListItem, "Second item"),
+            (BlockKind::Code, "fn main() {\n    run();\n}"),
+            (BlockKind::Table, "Name\tValue\na\t1"),
+            (BlockKind::Figure, "A caption"),
+            (BlockKind::Paragraph, "A line\nand another one."),
+        ]);
+        let text = get_blocks_text(&body);
+        assert!(text.starts_with("A heading\n\nThe first paragraph"));
+        for link in links.iter() {
+            let offset_text: String = text.chars().skip(link.offset).take(link.text.chars().count()).collect();
+            assert_eq!(offset_text, link.text);
+        }
+    }
+
+    #[test]
+    fn test_get_cleaned_body_and_links_normalized() {
+        let document = Document::from("<html><body><div>
+            <p>\n    A\u{A0}\u{A0}paragraph \t with   <a href=\"/a\">a  \u{201C}link\u{201D}</a>,<br>  \n <br><br> a soft\u{AD}hyphen
+            and <a href=\"/b\">\u{200B}another link</a>.  </p>
+            <pre>
+  keep   this
+</pre>
+            </div></body></html>");
+        let node = document.find(Name("div")).next().unwrap();
+        let normalization = TextNormalization {
+            replace_smart_quotes: true,
+            remove_soft_hyphens: true,
+            remove_zero_width_characters: true,
+            ..TextNormalization::default()
+        };
+        let (body, links) = get_cleaned_body_and_links(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), &normalization);
+        assert_eq!(body[0].text, "A paragraph with a \"link\",\n\na softhyphen and another link.");
+        assert_eq!(body[1].text, "  keep   this");
+        let text = get_blocks_text(&body);
+        let link_texts: Vec<&str> = links.iter().map(|link| link.text.as_str()).collect();
+        assert_eq!(link_texts, vec!["a \"link\"", "another link"]);
+        for link in links.iter() {
+            let offset_text: String = text.chars().skip(link.offset).take(link.text.chars().count()).collect();
+            assert_eq!(offset_text, link.text);
+        }
+    }
+
+    #[test]
+    fn test_mark_external_links() {
+        let mut links: Vec<Link> = ["/internal", "http://example.com/page", "https://www.other.com/", "mailto:me@example.com"].iter()
+            .map(|href| Link { href: String::from(*href), ..Link::default() })
+            .collect();
+        mark_external_links(&mut links, "https://www.example.com/article");
+        let flags: Vec<bool> = links.iter().map(|link| link.is_external).collect();
+        assert_eq!(flags, vec![false, false, true, false]);
+        mark_external_links(&mut links, "");
+        let flags: Vec<bool> = links.iter().map(|link| link.is_external).collect();
+        assert_eq!(flags, vec![false, true, true, false]);
     }
 }
\ No newline at end of file
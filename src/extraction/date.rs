@@ -0,0 +1,87 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+const DATE_TIME_WITH_OFFSET_FORMATS: [&str; 3] = [
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M%z",
+    "%Y-%m-%d %H:%M:%S%.f%z",
+];
+
+const DATE_TIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%a %b %e %H:%M:%S %Y",
+];
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%Y/%m/%d"];
+
+pub fn parse_date(raw: &str) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(raw) {
+        return Some(date.with_timezone(&Utc));
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(raw) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in DATE_TIME_WITH_OFFSET_FORMATS.iter() {
+        if let Ok(date) = DateTime::parse_from_str(raw, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+    }
+    for format in DATE_TIME_FORMATS.iter() {
+        if let Ok(date) = NaiveDateTime::parse_from_str(raw, format) {
+            return Some(Utc.from_utc_datetime(&date));
+        }
+    }
+    for format in DATE_FORMATS.iter() {
+        if let Ok(date) = NaiveDate::parse_from_str(raw, format) {
+            return date.and_hms_opt(0, 0, 0).map(|d| Utc.from_utc_datetime(&d));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(raw: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(raw).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_date_rfc3339() {
+        assert_eq!(parse_date("2015-11-13T07:40:07.000Z"), Some(utc("2015-11-13T07:40:07Z")));
+        assert_eq!(parse_date("2019-11-17T16:46:47+01:00"), Some(utc("2019-11-17T15:46:47Z")));
+    }
+
+    #[test]
+    fn test_parse_date_with_offset_without_colon() {
+        assert_eq!(parse_date("2016-06-24T03:35+0100"), Some(utc("2016-06-24T02:35:00Z")));
+        assert_eq!(parse_date("2015-11-13T07:40:07+0000"), Some(utc("2015-11-13T07:40:07Z")));
+    }
+
+    #[test]
+    fn test_parse_date_without_offset() {
+        assert_eq!(parse_date("2015-11-12T12:11:06.3270000"), Some(utc("2015-11-12T12:11:06.327Z")));
+        assert_eq!(parse_date("2015/02/20 15:42:28"), Some(utc("2015-02-20T15:42:28Z")));
+        assert_eq!(parse_date("Fri Nov 13 05:30:56 2015"), Some(utc("2015-11-13T05:30:56Z")));
+    }
+
+    #[test]
+    fn test_parse_date_only() {
+        assert_eq!(parse_date("2015-11-13"), Some(utc("2015-11-13T00:00:00Z")));
+        assert_eq!(parse_date("2016/06/24"), Some(utc("2016-06-24T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_parse_date_invalid() {
+        assert_eq!(parse_date(""), None);
+        assert_eq!(parse_date("2015-11-13T10:15:18.000Zyyyy-MM-dd HH:mm:ss z"), None);
+    }
+}
@@ -0,0 +1,327 @@
+use regex::Regex;
+use select::node::Node;
+use select::predicate::Name;
+
+use crate::article::{Image, ImageCandidate, SrcsetCandidate};
+
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+    static ref NUMBER_REGEX: Regex = Regex::new(r"^\s*(\d+)").unwrap();
+    static ref UNLIKELY_TOP_IMAGE_REGEX: Regex = Regex::new(r"(?i)(?:^|[^a-z])(logo|avatar|sprite|icon|badge|button|gravatar)s?(?:[^a-z]|$)").unwrap();
+    static ref IGNORED_IMAGE_NAME_REGEX: Regex = Regex::new(r"(?i)^(?:(?:spacer|blank|transparent|clear|pixel|1x1|tracking|beacon|favicon)[-_.]?)+(?:\.[a-z]+)?$|\.ico$").unwrap();
+    static ref IGNORED_IMAGE_DIRECTORY_REGEX: Regex = Regex::new(r"(?i)/(?:icons?|emojis?)/").unwrap();
+}
+
+const MIN_IMAGE_SIZE: u32 = 50;
+const MIN_TOP_IMAGE_SIZE: u32 = 100;
+const MAX_TOP_IMAGE_ASPECT_RATIO: f32 = 3.0;
+
+/// Collects the images of the content node with their `srcset` candidates, dimensions and
+/// captions. Tracking pixels, spacers and icons are left out.
+pub fn get_images(node: Node) -> Vec<Image> {
+    let mut images: Vec<Image> = Vec::new();
+    for img in node.find(Name("img")) {
+        let image = get_image(&img, &node);
+        if is_content_image(&image) && !images.iter().any(|i| i.src == image.src) {
+            images.push(image);
+        }
+    }
+    images
+}
+
+fn get_image(img: &Node, top_node: &Node) -> Image {
+    let in_picture = img.parent().and_then(|parent| parent.name()) == Some("picture");
+    let figure = find_ancestor(img, top_node, "figure");
+    let mut srcset: Vec<SrcsetCandidate> = Vec::new();
+    if in_picture {
+        for source in img.parent().unwrap().find(Name("source")) {
+            srcset.extend(parse_srcset(source.attr("srcset").or_else(|| source.attr("data-srcset")).unwrap_or_default()));
+        }
+    }
+    srcset.extend(parse_srcset(img.attr("srcset").or_else(|| img.attr("data-srcset")).unwrap_or_default()));
+    Image {
+        src: get_image_src(img),
+        srcset,
+        alt: String::from(img.attr("alt").unwrap_or_default().trim()),
+        width: img.attr("width").and_then(parse_dimension),
+        height: img.attr("height").and_then(parse_dimension),
+        caption: match figure.and_then(|f| f.find(Name("figcaption")).next()) {
+            Some(caption) => String::from(SPACES_REGEX.replace_all(caption.text().trim(), " ")),
+            _ => String::new()
+        },
+        in_picture,
+        in_figure: figure.is_some(),
+    }
+}
+
+fn find_ancestor<'a>(node: &Node<'a>, top_node: &Node, name: &str) -> Option<Node<'a>> {
+    let mut parent = node.parent();
+    while let Some(ancestor) = parent {
+        if ancestor.name() == Some(name) {
+            return Some(ancestor);
+        }
+        if ancestor.index() == top_node.index() {
+            return None;
+        }
+        parent = ancestor.parent();
+    }
+    None
+}
+
+/// Lazy-loaded images keep their actual URL in a `data-` attribute and a placeholder in `src`.
+pub fn get_image_src(img: &Node) -> String {
+    let src = img.attr("src").unwrap_or_default().trim();
+    if src.is_empty() || src.starts_with("data:") {
+        for attr in ["data-src", "data-lazy-src", "data-original"].iter() {
+            if let Some(lazy_src) = img.attr(attr) {
+                return String::from(lazy_src.trim());
+            }
+        }
+    }
+    String::from(src)
+}
+
+fn parse_dimension(raw: &str) -> Option<u32> {
+    NUMBER_REGEX.captures(raw).and_then(|c| c[1].parse().ok())
+}
+
+pub fn parse_srcset(srcset: &str) -> Vec<SrcsetCandidate> {
+    srcset.split(',')
+        .filter_map(|candidate| {
+            let mut parts = candidate.split_whitespace();
+            parts.next().map(|url| SrcsetCandidate {
+                url: String::from(url),
+                descriptor: String::from(parts.next().unwrap_or_default()),
+            })
+        })
+        .collect()
+}
+
+/// The path of an image URL, without its scheme, host, query and fragment.
+fn get_url_path(src: &str) -> &str {
+    let host_start = match src.find("://") {
+        Some(idx) => Some(idx + 3),
+        _ if src.starts_with("//") => Some(2),
+        _ => None
+    };
+    let path = match host_start {
+        Some(idx) => src[idx..].find('/').map_or("", |path_start| &src[idx + path_start..]),
+        _ => src
+    };
+    path.split(['?', '#']).next().unwrap_or_default()
+}
+
+/// Tells whether an image URL points to a tracking pixel, a spacer or an icon, judging from its
+/// file name and directories only, so that hosts such as `pixel.example.com` are not rejected.
+fn is_ignored_image(src: &str) -> bool {
+    let path = get_url_path(src);
+    let file_name = path.rsplit('/').next().unwrap_or_default();
+    IGNORED_IMAGE_NAME_REGEX.is_match(file_name) || IGNORED_IMAGE_DIRECTORY_REGEX.is_match(path)
+}
+
+fn is_content_image(image: &Image) -> bool {
+    if image.src.is_empty() || image.src.starts_with("data:") || is_ignored_image(&image.src) {
+        return false;
+    }
+    let too_small = |dimension: Option<u32>| dimension.is_some_and(|d| d < MIN_IMAGE_SIZE);
+    !too_small(image.width) && !too_small(image.height)
+}
+
+/// Scores the images of the content node and of its parent, to pick a top image when the page
+/// has no meta image. The best candidate which is not rejected is flagged as selected.
+pub fn get_top_image_candidates(node: Node) -> Vec<ImageCandidate> {
+    let scope = node.parent().unwrap_or(node);
+    let mut candidates: Vec<ImageCandidate> = Vec::new();
+    for (position, img) in scope.find(Name("img")).enumerate() {
+        let image = get_image(&img, &scope);
+        if candidates.iter().any(|c| c.src == image.src) {
+            continue;
+        }
+        let in_content_node = is_descendant(&img, &node);
+        candidates.push(score_image(image, position, in_content_node));
+    }
+    let selected = candidates.iter()
+        .enumerate()
+        .filter(|(_, c)| !c.rejected)
+        .max_by(|(i, a), (j, b)| a.score.cmp(&b.score).then(j.cmp(i)))
+        .map(|(i, _)| i);
+    if let Some(i) = selected {
+        candidates[i].selected = true;
+        candidates[i].reasons.push(String::from("selected: highest score"));
+    }
+    candidates
+}
+
+fn is_descendant(node: &Node, ancestor: &Node) -> bool {
+    let mut parent = node.parent();
+    while let Some(p) = parent {
+        if p.index() == ancestor.index() {
+            return true;
+        }
+        parent = p.parent();
+    }
+    false
+}
+
+fn score_image(image: Image, position: usize, in_content_node: bool) -> ImageCandidate {
+    let mut candidate = ImageCandidate { src: image.src.clone(), ..ImageCandidate::default() };
+    if image.src.is_empty() || image.src.starts_with("data:") {
+        candidate.reject("rejected: no source");
+        return candidate;
+    }
+    if is_ignored_image(&image.src) {
+        candidate.reject("rejected: tracking pixel, spacer or icon URL");
+        return candidate;
+    }
+    if let Some(hint) = UNLIKELY_TOP_IMAGE_REGEX.captures(get_url_path(&image.src)) {
+        candidate.reject(&format!("rejected: filename hint \"{}\"", &hint[1]));
+        return candidate;
+    }
+    match (image.width, image.height) {
+        (Some(width), Some(height)) => {
+            if width < MIN_TOP_IMAGE_SIZE || height < MIN_TOP_IMAGE_SIZE {
+                candidate.reject(&format!("rejected: too small ({}x{})", width, height));
+                return candidate;
+            }
+            let aspect_ratio = width as f32 / height as f32;
+            if !(1.0 / MAX_TOP_IMAGE_ASPECT_RATIO..=MAX_TOP_IMAGE_ASPECT_RATIO).contains(&aspect_ratio) {
+                candidate.reject(&format!("rejected: aspect ratio {:.2}", aspect_ratio));
+                return candidate;
+            }
+            let size_score = ((width as u64 * height as u64) / 10_000).min(50) as i32;
+            candidate.add(size_score, &format!("declared size {}x{}", width, height));
+            if (0.5..=2.0).contains(&aspect_ratio) {
+                candidate.add(10, &format!("aspect ratio {:.2}", aspect_ratio));
+            }
+        }
+        (Some(dimension), None) | (None, Some(dimension)) if dimension < MIN_TOP_IMAGE_SIZE => {
+            candidate.reject(&format!("rejected: too small ({}px)", dimension));
+            return candidate;
+        }
+        _ => candidate.add(5, "no declared size")
+    }
+    if in_content_node {
+        candidate.add(20, "inside the content node");
+    }
+    let position_score = 10 - (2 * position.min(5)) as i32;
+    if position_score > 0 {
+        candidate.add(position_score, &format!("position {}", position + 1));
+    }
+    if image.in_figure {
+        candidate.add(10, "inside a figure");
+    }
+    if !image.alt.is_empty() {
+        candidate.add(5, "has an alt text");
+    }
+    candidate
+}
+
+impl ImageCandidate {
+    fn add(&mut self, score: i32, reason: &str) {
+        self.score += score;
+        self.reasons.push(format!("{} (+{})", reason, score));
+    }
+
+    fn reject(&mut self, reason: &str) {
+        self.rejected = true;
+        self.reasons.push(String::from(reason));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use select::document::Document;
+    use select::predicate::Attr;
+
+    use super::*;
+
+    #[test]
+    fn test_get_images() {
+        let document = Document::from(r#"<div id="content">
+            <figure><picture><source srcset="/large.webp 1200w, /medium.webp 600w"><img src="/photo.jpg" alt=" A photo " width="800px" height="600"></picture>
+            <figcaption>The   caption</figcaption></figure>
+            <p><img src="/pixel.gif" width="1" height="1"><img src="/img/spacer.gif"><img src="/avatar.png" width="32" height="32"></p>
+            <img src="data:image/gif;base64,R0lGODlhAQABAAAAACw=" data-src="/lazy.jpg" srcset="/lazy-2x.jpg 2x">
+            <img src="/photo.jpg">
+        </div>"#);
+        let node = document.find(Name("div")).next().unwrap();
+        let images = get_images(node);
+        assert_eq!(images.len(), 2);
+        let image = &images[0];
+        assert_eq!(image.src, "/photo.jpg");
+        assert_eq!(image.alt, "A photo");
+        assert_eq!(image.width, Some(800));
+        assert_eq!(image.height, Some(600));
+        assert_eq!(image.caption, "The caption");
+        assert!(image.in_picture);
+        assert!(image.in_figure);
+        assert_eq!(image.srcset.len(), 2);
+        assert_eq!(image.srcset[0], SrcsetCandidate { url: String::from("/large.webp"), descriptor: String::from("1200w") });
+        let image = &images[1];
+        assert_eq!(image.src, "/lazy.jpg");
+        assert_eq!(image.srcset[0].url, "/lazy-2x.jpg");
+        assert!(!image.in_figure);
+    }
+
+    #[test]
+    fn test_is_ignored_image() {
+        assert!(is_ignored_image("https://www.example.com/img/tracking-pixel.gif?id=1"));
+        assert!(is_ignored_image("/static/icons/search.svg"));
+        assert!(is_ignored_image("//www.example.com/favicon.ico"));
+        assert!(!is_ignored_image("https://cdn.pixel.example/photo.jpg"));
+        assert!(!is_ignored_image("/2015/11/tracking-the-storm.jpg"));
+        assert!(!is_ignored_image("https://www.example.com/news/silicon-valley.jpg"));
+    }
+
+    #[test]
+    fn test_parse_srcset() {
+        let candidates = parse_srcset("a.jpg, b.jpg 2x,");
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].descriptor, "");
+        assert_eq!(candidates[1].descriptor, "2x");
+    }
+
+    #[test]
+    fn test_get_top_image_candidates() {
+        let document = Document::from(r#"<body><header><img src="/logo.png" width="300" height="300"></header>
+            <main><img src="/banner.jpg" width="1200" height="100"><img src="/hero.jpg" width="1024" height="768">
+            <div id="content"><p>Text</p><figure><img src="/photo.jpg" alt="Photo" width="400" height="300"></figure>
+            <img src="/thumb.jpg" width="60" height="60"></div></main></body>"#);
+        let node = document.find(Attr("id", "content")).next().unwrap();
+        let candidates = get_top_image_candidates(node);
+        let srcs: Vec<&str> = candidates.iter().map(|c| c.src.as_str()).collect();
+        assert_eq!(srcs, vec!["/banner.jpg", "/hero.jpg", "/photo.jpg", "/thumb.jpg"]);
+        assert!(candidates[0].rejected);
+        assert_eq!(candidates[0].reasons, vec!["rejected: aspect ratio 12.00"]);
+        assert!(candidates[3].rejected);
+        let selected: Vec<&str> = candidates.iter().filter(|c| c.selected).map(|c| c.src.as_str()).collect();
+        assert_eq!(selected, vec!["/hero.jpg"]);
+    }
+
+    #[test]
+    fn test_get_top_image_candidates_filename_hint() {
+        let document = Document::from(r#"<div><div id="content"><img src="/img/user-avatar.jpg"><p>Text</p></div></div>"#);
+        let node = document.find(Attr("id", "content")).next().unwrap();
+        let candidates = get_top_image_candidates(node);
+        assert_eq!(candidates[0].reasons, vec!["rejected: filename hint \"avatar\""]);
+        assert!(!candidates[0].selected);
+    }
+
+    #[test]
+    fn test_get_top_image_candidates_filename_hint_in_word() {
+        let document = Document::from(r#"<div><div id="content"><img src="https://icons.example.com/news/silicon-valley.jpg"><p>Text</p></div></div>"#);
+        let node = document.find(Attr("id", "content")).next().unwrap();
+        let candidates = get_top_image_candidates(node);
+        assert!(!candidates[0].rejected);
+        assert!(candidates[0].selected);
+    }
+
+    #[test]
+    fn test_get_top_image_candidates_huge_size() {
+        let document = Document::from(r#"<div><div id="content"><img src="/huge.jpg" width="70000" height="70000"><p>Text</p></div></div>"#);
+        let node = document.find(Attr("id", "content")).next().unwrap();
+        let candidates = get_top_image_candidates(node);
+        assert_eq!(candidates[0].reasons[0], "declared size 70000x70000 (+50)");
+        assert!(candidates[0].selected);
+    }
+}
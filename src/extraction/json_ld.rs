@@ -0,0 +1,143 @@
+use regex::Regex;
+use select::document::Document;
+use select::predicate::{Attr, Name, Predicate};
+use serde_json::Value;
+
+lazy_static! {
+    static ref CDATA_REGEX: Regex = Regex::new(r"^\s*(?://|/\*)?\s*<!\[CDATA\[(?:\s*\*/)?|(?://|/\*)?\s*\]\]>(?:\s*\*/)?\s*$").unwrap();
+}
+
+const ARTICLE_TYPES: [&str; 11] = [
+    "Article",
+    "NewsArticle",
+    "BlogPosting",
+    "LiveBlogPosting",
+    "ReportageNewsArticle",
+    "AnalysisNewsArticle",
+    "OpinionNewsArticle",
+    "ReviewNewsArticle",
+    "TechArticle",
+    "ScholarlyArticle",
+    "NewsItem",
+];
+
+/// Parses every `<script type="application/ld+json">` block and returns their items, with the
+/// top-level arrays and `@graph` arrays flattened.
+pub fn get_json_ld_items(document: &Document) -> Vec<Value> {
+    let mut items: Vec<Value> = Vec::new();
+    for node in document.find(Name("script").and(Attr("type", "application/ld+json"))) {
+        let text = node.text();
+        if let Ok(value) = serde_json::from_str::<Value>(&CDATA_REGEX.replace_all(&text, "")) {
+            flatten_items(value, &mut items);
+        }
+    }
+    items
+}
+
+fn flatten_items(value: Value, items: &mut Vec<Value>) {
+    match value {
+        Value::Array(values) => values.into_iter().for_each(|v| flatten_items(v, items)),
+        Value::Object(mut map) => match map.remove("@graph") {
+            Some(graph) => {
+                flatten_items(graph, items);
+                if map.keys().any(|key| key != "@context") {
+                    items.push(Value::Object(map));
+                }
+            }
+            _ => items.push(Value::Object(map))
+        },
+        _ => ()
+    }
+}
+
+/// Tells whether a schema.org type, possibly given as a full URL (`http://schema.org/NewsArticle`)
+/// or a prefixed name (`rnews:NewsItem`), is an article type.
+pub fn is_article_type(item_type: &str) -> bool {
+    ARTICLE_TYPES.contains(&get_local_name(item_type))
+}
+
+/// Strips the vocabulary of an URL or a prefixed name.
+pub fn get_local_name(name: &str) -> &str {
+    match name.rfind(['/', ':', '#']) {
+        Some(idx) => &name[idx + 1..],
+        _ => name
+    }
+}
+
+pub fn is_article_item(item: &Value) -> bool {
+    match item.get("@type") {
+        Some(Value::String(t)) => is_article_type(t),
+        Some(Value::Array(types)) => types.iter()
+            .any(|t| t.as_str().is_some_and(is_article_type)),
+        _ => false
+    }
+}
+
+pub fn get_article_item(items: &[Value]) -> Option<&Value> {
+    items.iter().find(|item| is_article_item(item))
+}
+
+/// Reads a textual value out of a JSON-LD property, which can be a plain string, a value or list
+/// object (`@value`, `@list`), a nested object such as an `ImageObject` or a `Person`, or an array.
+pub fn get_json_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            if s.is_empty() { None } else { Some(String::from(s)) }
+        }
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(values) => values.iter().find_map(get_json_text),
+        Value::Object(map) => ["@value", "@list", "url", "name"].iter()
+            .filter_map(|key| map.get(*key))
+            .find_map(get_json_text),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_json_ld_items_graph() {
+        let document = Document::from(r#"<html><script type="application/ld+json">
+            {"@context": "http://schema.org", "@graph": [{"@type": "WebPage"}, {"@type": "NewsArticle", "headline": "Headline"}]}
+            </script></html>"#);
+        let items = get_json_ld_items(&document);
+        assert_eq!(items.len(), 2);
+        assert_eq!(get_article_item(&items).unwrap()["headline"], "Headline");
+    }
+
+    #[test]
+    fn test_get_json_ld_items_array_figaro() {
+        let document = Document::from(include_str!("sites/figaro.fr.html"));
+        let items = get_json_ld_items(&document);
+        assert_eq!(items.len(), 3);
+        assert_eq!(get_article_item(&items).unwrap()["@type"], "NewsArticle");
+    }
+
+    #[test]
+    fn test_get_json_ld_items_cdata_businessinsider() {
+        let document = Document::from(include_str!("sites/businessinsider.com.html"));
+        let items = get_json_ld_items(&document);
+        assert_eq!(items.len(), 1);
+        assert!(is_article_item(&items[0]));
+    }
+
+    #[test]
+    fn test_is_article_type() {
+        assert!(is_article_type("NewsArticle"));
+        assert!(is_article_type("http://schema.org/BlogPosting"));
+        assert!(is_article_type("rnews:NewsItem"));
+        assert!(!is_article_type("http://schema.org/Person"));
+    }
+
+    #[test]
+    fn test_get_json_text() {
+        let value: Value = serde_json::from_str(r#"{"a": " text ", "b": {"@list": ["first", "second"]}, "c": [{"@type": "Person", "name": "Name"}], "d": ""}"#).unwrap();
+        assert_eq!(get_json_text(&value["a"]).unwrap(), "text");
+        assert_eq!(get_json_text(&value["b"]).unwrap(), "first");
+        assert_eq!(get_json_text(&value["c"]).unwrap(), "Name");
+        assert_eq!(get_json_text(&value["d"]), None);
+    }
+}
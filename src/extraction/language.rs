@@ -0,0 +1,277 @@
+use std::collections::{BTreeMap, HashMap};
+
+use select::document::Document;
+use select::node::Node;
+use select::predicate::Name;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::extraction::stopwords::get_stopword_lists;
+
+const MIN_LETTERS: usize = 20;
+const MAX_SAMPLE_CHARS: usize = 20_000;
+const MIN_SCRIPT_RATIO: f64 = 0.5;
+const MIN_VIETNAMESE_RATIO: f64 = 0.05;
+const MIN_CJK_RATIO: f64 = 0.3;
+const MIN_KANA_RATIO: f64 = 0.05;
+const MIN_TRIGRAM_SCORE: f64 = 0.05;
+
+lazy_static! {
+    /// Trigram profiles of the languages having a stopword list. Stopwords are the most frequent
+    /// words of a language, so the trigrams of the padded stopwords (` th`, `the`, `he `) make a
+    /// small but discriminating profile. Each trigram is weighted by the inverse of the number of
+    /// languages sharing it.
+    static ref TRIGRAM_PROFILES: BTreeMap<&'static str, HashMap<String, f64>> = {
+        let trigram_sets: BTreeMap<&'static str, Vec<String>> = get_stopword_lists().iter()
+            .map(|(lang, stopwords)| {
+                let mut trigrams: Vec<String> = stopwords.iter().flat_map(|word| get_trigrams(word)).collect();
+                trigrams.sort();
+                trigrams.dedup();
+                (*lang, trigrams)
+            })
+            .collect();
+        let mut languages_per_trigram: HashMap<&str, usize> = HashMap::new();
+        for trigram in trigram_sets.values().flatten() {
+            *languages_per_trigram.entry(trigram).or_default() += 1;
+        }
+        trigram_sets.iter()
+            .map(|(lang, trigrams)| {
+                let profile = trigrams.iter()
+                    .map(|trigram| (trigram.clone(), 1.0 / languages_per_trigram[trigram.as_str()] as f64))
+                    .collect();
+                (*lang, profile)
+            })
+            .collect()
+    };
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+    Other,
+}
+
+fn get_script(c: char) -> Script {
+    match c as u32 {
+        0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+        0x0400..=0x052F => Script::Cyrillic,
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+        0x0600..=0x06FF | 0x0750..=0x077F | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0900..=0x097F => Script::Devanagari,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+        0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Script::Kana,
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => Script::Han,
+        _ => Script::Other
+    }
+}
+
+/// Reduces an ISO 639-1, ISO 639-2 or BCP-47 language tag (`pt-BR`, `zh-Hant`, `fre`, `en_US`)
+/// to its lowercased ISO 639-1 code. Unknown codes are returned lowercased.
+pub fn normalize_language_code(code: &str) -> String {
+    let primary_subtag = code.trim().split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    let iso_639_1 = match primary_subtag.as_str() {
+        "ara" => "ar",
+        "aze" => "az",
+        "ces" | "cze" => "cs",
+        "dan" => "da",
+        "deu" | "ger" => "de",
+        "ell" | "gre" => "el",
+        "eng" => "en",
+        "spa" => "es",
+        "fin" => "fi",
+        "fra" | "fre" => "fr",
+        "heb" | "iw" => "he",
+        "hin" => "hi",
+        "hun" => "hu",
+        "ind" | "in" => "id",
+        "ita" => "it",
+        "jpn" => "ja",
+        "kaz" => "kk",
+        "kor" => "ko",
+        "nep" => "ne",
+        "nld" | "dut" => "nl",
+        "nor" | "nob" | "nno" | "nb" | "nn" => "no",
+        "pol" => "pl",
+        "por" => "pt",
+        "ron" | "rum" => "ro",
+        "rus" => "ru",
+        "swe" => "sv",
+        "tha" => "th",
+        "tur" => "tr",
+        "vie" => "vi",
+        "zho" | "chi" | "cmn" => "zh",
+        _ => return primary_subtag
+    };
+    String::from(iso_639_1)
+}
+
+/// Vietnamese is written with the Latin script, but it is the only language using `đ`, `ơ`, `ư`
+/// and the stacked tone marks of the Latin Extended Additional block.
+fn is_vietnamese_letter(c: char) -> bool {
+    matches!(c, 'đ' | 'Đ' | 'ơ' | 'Ơ' | 'ư' | 'Ư' | 'ă' | 'Ă') || ('\u{1EA0}'..='\u{1EF9}').contains(&c)
+}
+
+fn get_trigrams(word: &str) -> Vec<String> {
+    let chars: Vec<char> = format!(" {} ", word.to_lowercase()).chars().collect();
+    chars.windows(3).map(|window| window.iter().collect()).collect()
+}
+
+/// Identifies the language of a text. Scripts used by a single language (Hangul, kana, Thai...)
+/// are enough on their own; the others are scored against the trigram profiles. Returns the
+/// ISO 639-1 code of the language along with a confidence between 0 and 1.
+///
+/// Devanagari is reported as Hindi, its most common language, which has no stopword list: the
+/// content of those pages is then found by text density rather than by stopwords.
+pub fn detect_language(text: &str) -> Option<(String, f64)> {
+    let mut script_counts: Vec<(Script, usize)> = Vec::new();
+    let mut nb_letters: usize = 0;
+    let mut nb_vietnamese_letters: usize = 0;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        let script = get_script(c);
+        match script_counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            _ => script_counts.push((script, 1))
+        }
+        nb_letters += 1;
+        if is_vietnamese_letter(c) {
+            nb_vietnamese_letters += 1;
+        }
+    }
+    if nb_letters < MIN_LETTERS {
+        return None;
+    }
+    let count_of = |script: Script| script_counts.iter().find(|(s, _)| *s == script).map_or(0, |(_, count)| *count);
+    let ratio_of = |count: usize| count as f64 / nb_letters as f64;
+
+    let nb_cjk_letters = count_of(Script::Han) + count_of(Script::Kana);
+    if ratio_of(count_of(Script::Hangul)) >= MIN_CJK_RATIO {
+        return Some((String::from("ko"), ratio_of(count_of(Script::Hangul))));
+    }
+    if ratio_of(nb_cjk_letters) >= MIN_CJK_RATIO {
+        let lang = if count_of(Script::Kana) as f64 / nb_cjk_letters as f64 >= MIN_KANA_RATIO { "ja" } else { "zh" };
+        return Some((String::from(lang), ratio_of(nb_cjk_letters)));
+    }
+    let single_language_scripts = [(Script::Thai, "th"), (Script::Greek, "el"), (Script::Hebrew, "he"), (Script::Devanagari, "hi")];
+    for (script, lang) in single_language_scripts.iter() {
+        if ratio_of(count_of(*script)) >= MIN_SCRIPT_RATIO {
+            return Some((String::from(*lang), ratio_of(count_of(*script))));
+        }
+    }
+    let nb_latin_letters = count_of(Script::Latin);
+    if ratio_of(nb_latin_letters) >= MIN_SCRIPT_RATIO && nb_vietnamese_letters as f64 / nb_latin_letters as f64 >= MIN_VIETNAMESE_RATIO {
+        return Some((String::from("vi"), ratio_of(nb_latin_letters)));
+    }
+    detect_language_from_trigrams(text)
+}
+
+fn detect_language_from_trigrams(text: &str) -> Option<(String, f64)> {
+    let mut trigram_counts: HashMap<String, usize> = HashMap::new();
+    for word in text.unicode_words() {
+        for trigram in get_trigrams(word) {
+            *trigram_counts.entry(trigram).or_default() += 1;
+        }
+    }
+    let nb_trigrams: usize = trigram_counts.values().sum();
+    if nb_trigrams == 0 {
+        return None;
+    }
+    let mut scores: Vec<(&str, f64)> = TRIGRAM_PROFILES.iter()
+        .map(|(lang, profile)| {
+            let score: f64 = trigram_counts.iter()
+                .filter_map(|(trigram, count)| profile.get(trigram).map(|weight| weight * *count as f64))
+                .sum();
+            (*lang, score / nb_trigrams as f64 / (profile.len() as f64).sqrt() * 20.0)
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    match scores.as_slice() {
+        [(lang, best), rest @ ..] if *best >= MIN_TRIGRAM_SCORE => {
+            let second = rest.first().map_or(0.0, |(_, score)| *score);
+            Some((String::from(*lang), (best - second) / best))
+        }
+        _ => None
+    }
+}
+
+/// Gathers a sample of the visible text of the body to run the detection on.
+pub fn get_body_text(document: &Document) -> String {
+    let mut text = String::new();
+    if let Some(body) = document.find(Name("body")).next() {
+        collect_text(body, &mut text);
+    }
+    text
+}
+
+fn collect_text(node: Node, text: &mut String) {
+    for child in node.children() {
+        if text.len() >= MAX_SAMPLE_CHARS {
+            return;
+        }
+        match child.name() {
+            Some("script") | Some("style") | Some("noscript") | Some("template") => (),
+            Some(_) => collect_text(child, text),
+            _ => if let Some(s) = child.as_text().map(str::trim).filter(|s| !s.is_empty()) {
+                text.push_str(s);
+                text.push(' ');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_from_fixture(raw_html: &str) -> Option<(String, f64)> {
+        detect_language(&get_body_text(&Document::from(raw_html)))
+    }
+
+    #[test]
+    fn test_detect_language_latin() {
+        assert_eq!(detect_from_fixture(include_str!("sites/theguardian.com.html")).unwrap().0, "en");
+        assert_eq!(detect_from_fixture(include_str!("sites/figaro.fr.html")).unwrap().0, "fr");
+        assert_eq!(detect_from_fixture(include_str!("sites/emeia.ey-vx.com.html")).unwrap().0, "no");
+        assert_eq!(detect_from_fixture(include_str!("sites/globoesporte.globo.com.html")).unwrap().0, "pt");
+        assert_eq!(detect_language("Der schnelle braune Fuchs springt über den faulen Hund, und die Katze schläft auf dem Sofa.").unwrap().0, "de");
+        assert_eq!(detect_language("El rápido zorro marrón salta sobre el perro perezoso y el gato duerme en el sofá de la casa.").unwrap().0, "es");
+    }
+
+    #[test]
+    fn test_detect_language_scripts() {
+        assert_eq!(detect_from_fixture(include_str!("sites/huffingtonpost.jp.html")).unwrap().0, "ja");
+        assert_eq!(detect_from_fixture(include_str!("sites/vnexpress.net.html")).unwrap().0, "vi");
+        assert_eq!(detect_language("이 기사는 한국어로 작성되었으며 언어 감지 테스트를 위한 것입니다").unwrap().0, "ko");
+        assert_eq!(detect_language("这篇文章是用中文写的，用于测试语言检测功能是否正常工作").unwrap().0, "zh");
+        assert_eq!(detect_language("यह लेख हिंदी में लिखा गया है और भाषा की पहचान की जांच के लिए है").unwrap().0, "hi");
+    }
+
+    #[test]
+    fn test_normalize_language_code() {
+        assert_eq!(normalize_language_code("pt-BR"), "pt");
+        assert_eq!(normalize_language_code("zh-Hant"), "zh");
+        assert_eq!(normalize_language_code(" en_US "), "en");
+        assert_eq!(normalize_language_code("fre"), "fr");
+        assert_eq!(normalize_language_code("SWE"), "sv");
+        assert_eq!(normalize_language_code("nb-NO"), "no");
+        assert_eq!(normalize_language_code("iw"), "he");
+        assert_eq!(normalize_language_code("xx"), "xx");
+        assert_eq!(normalize_language_code(""), "");
+    }
+
+    #[test]
+    fn test_detect_language_confidence() {
+        let (_, confidence) = detect_from_fixture(include_str!("sites/theguardian.com.html")).unwrap();
+        assert!(confidence > 0.0 && confidence <= 1.0);
+        assert_eq!(detect_language("12 34 !!"), None);
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use select::node::Node;
+
+use crate::extraction::image::get_image_src;
+use crate::extraction::resolver::{resolve_url, UrlResolver};
+
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+    static ref ORDERED_LIST_START_REGEX: Regex = Regex::new(r"^(\d+)([.)])").unwrap();
+    static ref CODE_LANGUAGE_REGEX: Regex = Regex::new(r"(?:^|\s)lang(?:uage)?-([\w+#-]+)").unwrap();
+}
+
+const BLOCK_TAGS: [&str; 35] = [
+    "address", "article", "aside", "blockquote", "dd", "details", "div", "dl", "dt", "fieldset",
+    "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr",
+    "li", "main", "nav", "ol", "p", "pre", "section", "table", "tbody", "td", "th", "tr", "ul",
+];
+
+const IGNORED_TAGS: [&str; 9] = ["script", "style", "noscript", "template", "iframe", "object", "button", "svg", "select"];
+
+/// Renders the top node as CommonMark, leaving out the removed nodes. The URLs of the links and
+/// images are resolved when a resolver is given; a link whose URL is dropped by the resolver is
+/// rendered as its text.
+pub fn get_markdown(node: Node, excluded_nodes: &HashSet<usize>, resolver: Option<&UrlResolver>) -> String {
+    let renderer = MarkdownRenderer { excluded_nodes, resolver };
+    renderer.render_blocks(node).join("\n\n")
+}
+
+struct MarkdownRenderer<'a> {
+    excluded_nodes: &'a HashSet<usize>,
+    resolver: Option<&'a UrlResolver>,
+}
+
+impl<'a> MarkdownRenderer<'a> {
+    fn is_rendered(&self, node: &Node) -> bool {
+        !self.excluded_nodes.contains(&node.index()) && !node.name().is_some_and(|name| IGNORED_TAGS.contains(&name))
+    }
+
+    /// Renders the children of a node, the runs of inline content becoming paragraphs.
+    fn render_blocks(&self, node: Node) -> Vec<String> {
+        let mut blocks: Vec<String> = Vec::new();
+        let mut inline = String::new();
+        for child in node.children().filter(|child| self.is_rendered(child)) {
+            match child.name() {
+                Some(name) if BLOCK_TAGS.contains(&name) => {
+                    push_paragraph(&mut blocks, &mut inline);
+                    blocks.extend(self.render_block(child, name));
+                }
+                _ => self.render_inline(child, &mut inline)
+            }
+        }
+        push_paragraph(&mut blocks, &mut inline);
+        blocks
+    }
+
+    fn render_block(&self, node: Node, name: &str) -> Vec<String> {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level: usize = name[1..].parse().unwrap_or(1);
+                let text = self.render_inline_children(node);
+                if text.is_empty() {
+                    return Vec::new();
+                }
+                vec![format!("{} {}", "#".repeat(level), text)]
+            }
+            "ul" | "ol" => {
+                let start: usize = node.attr("start").and_then(|start| start.trim().parse().ok()).unwrap_or(1);
+                let items: Vec<String> = node.children()
+                    .filter(|child| child.name() == Some("li") && self.is_rendered(child))
+                    .map(|item| self.render_blocks(item).join("\n\n"))
+                    .filter(|item| !item.is_empty())
+                    .enumerate()
+                    .map(|(i, item)| {
+                        let marker = if name == "ol" { format!("{}. ", start + i) } else { String::from("- ") };
+                        prefix_lines(&item, &marker, &" ".repeat(marker.len()))
+                    })
+                    .collect();
+                if items.is_empty() {
+                    return Vec::new();
+                }
+                vec![items.join("\n")]
+            }
+            "blockquote" => {
+                let quote = self.render_blocks(node).join("\n\n");
+                if quote.is_empty() {
+                    return Vec::new();
+                }
+                vec![prefix_lines(&quote, "> ", "> ")]
+            }
+            "pre" => {
+                let code = node.text();
+                let code = code.trim_start_matches(['\n', '\r']).trim_end();
+                if code.is_empty() {
+                    return Vec::new();
+                }
+                let fence = "`".repeat(get_longest_backtick_run(code).max(2) + 1);
+                vec![format!("{}{}\n{}\n{}", fence, get_code_language(&node), code, fence)]
+            }
+            "hr" => vec![String::from("---")],
+            _ => self.render_blocks(node)
+        }
+    }
+
+    fn render_inline_children(&self, node: Node) -> String {
+        let mut inline = String::new();
+        for child in node.children().filter(|child| self.is_rendered(child)) {
+            self.render_inline(child, &mut inline);
+        }
+        String::from(inline.trim())
+    }
+
+    fn render_inline(&self, node: Node, out: &mut String) {
+        if !self.is_rendered(&node) {
+            return;
+        }
+        if let Some(text) = node.as_text() {
+            let text = SPACES_REGEX.replace_all(text, " ");
+            let text = if out.is_empty() || out.ends_with([' ', '\n']) { text.trim_start() } else { &text };
+            out.push_str(&escape(text));
+            return;
+        }
+        match node.name() {
+            Some("br") => out.push_str("  \n"),
+            Some("em") | Some("i") => push_delimited(out, "*", &self.render_inline_children(node), &node),
+            Some("strong") | Some("b") => push_delimited(out, "**", &self.render_inline_children(node), &node),
+            Some("code") | Some("kbd") | Some("samp") => {
+                let code = String::from(SPACES_REGEX.replace_all(node.text().trim(), " "));
+                if !code.is_empty() {
+                    let fence = "`".repeat(get_longest_backtick_run(&code) + 1);
+                    let padding = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+                    out.push_str(&format!("{}{}{}{}{}", fence, padding, code, padding, fence));
+                }
+            }
+            Some("a") => {
+                let text = self.render_inline_children(node);
+                match node.attr("href").and_then(|href| self.resolve(href)) {
+                    Some(url) if !text.is_empty() => {
+                        let title = node.attr("title").unwrap_or_default().trim();
+                        out.push_str(&format!("[{}]({}{})", text, format_destination(&url), format_title(title)));
+                    }
+                    _ => out.push_str(&text)
+                }
+            }
+            Some("img") => {
+                if let Some(src) = self.resolve(&get_image_src(&node)) {
+                    let alt = escape(&SPACES_REGEX.replace_all(node.attr("alt").unwrap_or_default().trim(), " "));
+                    out.push_str(&format!("![{}]({})", alt, format_destination(&src)));
+                }
+            }
+            _ => {
+                for child in node.children() {
+                    self.render_inline(child, out);
+                }
+            }
+        }
+    }
+
+    fn resolve(&self, raw_url: &str) -> Option<String> {
+        resolve_url(self.resolver, raw_url)
+    }
+}
+
+/// Ends a paragraph, escaping a start which would be read as a heading, a quote or a list item.
+fn push_paragraph(blocks: &mut Vec<String>, inline: &mut String) {
+    let paragraph = inline.trim();
+    if !paragraph.is_empty() {
+        let paragraph = if paragraph.starts_with(['#', '>', '-', '+']) {
+            format!("\\{}", paragraph)
+        } else {
+            String::from(ORDERED_LIST_START_REGEX.replace(paragraph, "$1\\$2"))
+        };
+        blocks.push(paragraph);
+    }
+    inline.clear();
+}
+
+/// Wraps an inline text in emphasis delimiters, which must not be next to spaces.
+fn push_delimited(out: &mut String, delimiter: &str, text: &str, node: &Node) {
+    if text.is_empty() {
+        return;
+    }
+    let node_text = node.text();
+    if node_text.starts_with(char::is_whitespace) && !out.is_empty() && !out.ends_with([' ', '\n']) {
+        out.push(' ');
+    }
+    out.push_str(delimiter);
+    out.push_str(text);
+    out.push_str(delimiter);
+    if node_text.ends_with(char::is_whitespace) {
+        out.push(' ');
+    }
+}
+
+fn prefix_lines(text: &str, first_prefix: &str, prefix: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { first_prefix } else { prefix };
+            if line.is_empty() { String::from(prefix.trim_end()) } else { format!("{}{}", prefix, line) }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn format_destination(url: &str) -> String {
+    if url.contains([' ', '(', ')']) {
+        format!("<{}>", url.replace('<', "%3C").replace('>', "%3E"))
+    } else {
+        String::from(url)
+    }
+}
+
+fn format_title(title: &str) -> String {
+    if title.is_empty() {
+        String::new()
+    } else {
+        format!(" \"{}\"", title.replace('"', "\\\""))
+    }
+}
+
+fn get_longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// Reads the language of a code block from a `language-` or `lang-` class of the `<pre>` or of
+/// its `<code>`.
+fn get_code_language(pre: &Node) -> String {
+    pre.attr("class").into_iter()
+        .chain(pre.children().filter(|child| child.name() == Some("code")).filter_map(|code| code.attr("class")))
+        .find_map(|class| CODE_LANGUAGE_REGEX.captures(class).map(|captures| String::from(&captures[1])))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use select::document::Document;
+    use select::predicate::Name;
+    use url::Url;
+
+    use crate::configuration::BoilerplatePatterns;
+    use crate::extraction::content::get_removed_nodes;
+
+    use super::*;
+
+    fn get_div_markdown(html: &str, resolver: Option<&UrlResolver>) -> String {
+        let document = Document::from(html);
+        let node = document.find(Name("div")).next().unwrap();
+        get_markdown(node, &get_removed_nodes(node, &BoilerplatePatterns::default()), resolver)
+    }
+
+    #[test]
+    fn test_get_markdown() {
+        let markdown = get_div_markdown(r#"<html><body><div>
+            <h2>A <em>heading</em></h2>
+            <p>A paragraph with <em>emphasis</em>, <strong>strong text</strong>, <code>code</code> and
+            <a href="/page" title="A page">a link</a>.</p>
+            <blockquote><p>A quote.</p><p>Second line.</p></blockquote>
+            <ul><li>First item</li><li>Second <b>item</b></li></ul>
+            <ol start="3"><li>Third</li></ol>
+            <pre><code class="language-rust">fn main() {
+    run();
+}</code></pre>
+            <figure><img src="/a.jpg" alt="An image"><figcaption>A caption</figcaption></figure>
+            <p>1. Not a list * and <a href="javascript:void(0)">no link</a><br>on two lines</p>
+            <script>var x = 1;</script>
+            </div></body></html>"#, None);
+        assert_eq!(markdown, "## A *heading*\n\n\
+            A paragraph with *emphasis*, **strong text**, `code` and [a link](/page \"A page\").\n\n\
+            > A quote.\n>\n> Second line.\n\n\
+            - First item\n- Second **item**\n\n\
+            3. Third\n\n\
+            ```rust\nfn main() {\n    run();\n}\n```\n\n\
+            ![An image](/a.jpg)\n\n\
+            A caption\n\n\
+            1\\. Not a list \\* and no link  \non two lines");
+    }
+
+    #[test]
+    fn test_get_markdown_resolved_urls() {
+        let resolver = UrlResolver::new(Url::parse("https://www.example.com/news/article.html").unwrap());
+        let markdown = get_div_markdown(r#"<div><p>A <a href="other.html">link</a>, <a href="mailto:me@example.com">a mail</a>
+            and <img src="data:image/gif;base64,R0lGOD" data-src="/lazy.jpg" alt="lazy">.</p></div>"#, Some(&resolver));
+        assert_eq!(markdown, "A [link](https://www.example.com/news/other.html), a mail and ![lazy](https://www.example.com/lazy.jpg).");
+    }
+
+    #[test]
+    fn test_get_markdown_removed_nodes() {
+        let markdown = get_div_markdown(r#"<div><p>A paragraph long enough to be kept in the text.</p>
+            <span>A short sentence outside of any paragraph</span></div>"#, None);
+        assert_eq!(markdown, "A paragraph long enough to be kept in the text.");
+    }
+
+    #[test]
+    fn test_get_markdown_nested_list() {
+        let markdown = get_div_markdown("<div><ul><li>Parent<ul><li>Child</li></ul></li></ul></div>", None);
+        assert_eq!(markdown, "- Parent\n\n  - Child");
+    }
+
+    #[test]
+    fn test_get_markdown_scripted_urls() {
+        let markdown = get_div_markdown(r#"<div><p><a href="JAVASCRIPT:alert(2)">upper</a>, <a href="java&#x09;script:alert(1)">a tab link</a>,
+            <a href="java&#x0A;script:alert(1)">a newline link</a>, <a href=" VBScript:msgbox(1)">a vbscript link</a>,
+            <a href="data:text/html;base64,PHNjcmlwdD4=">a data link</a> and <a href="/page">a page</a>
+            <img src="data:image/gif;base64,R0lGOD" alt="pixel"></p></div>"#, None);
+        assert_eq!(markdown, "upper, a tab link, a newline link, a vbscript link, a data link and [a page](/page)");
+    }
+}
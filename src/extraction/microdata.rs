@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Not, Predicate};
+
+use crate::article::{Item, ItemValue};
+use crate::extraction::json_ld::{get_local_name, is_article_type};
+
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+struct Annotation {
+    scope: &'static str,
+    item_type: &'static str,
+    property: &'static str,
+}
+
+const MICRODATA: Annotation = Annotation { scope: "itemscope", item_type: "itemtype", property: "itemprop" };
+const RDFA: Annotation = Annotation { scope: "typeof", item_type: "typeof", property: "property" };
+
+const PAGE_TAGS: [&str; 2] = ["html", "body"];
+
+/// Walks the microdata (`itemscope`, `itemtype`, `itemprop`) and RDFa (`typeof`, `property`)
+/// annotations of the document and returns its top-level items. Property names are stored
+/// without their vocabulary prefix. The items declared on `<html>` or `<body>` come last, as
+/// they gather the stray properties of the whole page, sidebars included.
+pub fn get_items(document: &Document) -> Vec<Item> {
+    let mut items: Vec<Item> = Vec::new();
+    let mut page_items: Vec<Item> = Vec::new();
+    for annotation in [MICRODATA, RDFA].iter() {
+        let top_level_predicate = Attr(annotation.scope, ()).and(Not(Attr(annotation.property, ())));
+        for node in document.find(top_level_predicate) {
+            let item = parse_item(node, annotation);
+            if PAGE_TAGS.contains(&node.name().unwrap_or_default()) {
+                page_items.push(item);
+            } else {
+                items.push(item);
+            }
+        }
+    }
+    items.extend(page_items);
+    items
+}
+
+fn parse_item(node: Node, annotation: &Annotation) -> Item {
+    let item_type = node.attr(annotation.item_type).unwrap_or_default();
+    let mut item = Item {
+        item_type: item_type.split_whitespace().map(String::from).collect(),
+        properties: BTreeMap::new(),
+    };
+    collect_properties(node, annotation, &mut item.properties);
+    item
+}
+
+fn collect_properties(node: Node, annotation: &Annotation, properties: &mut BTreeMap<String, Vec<ItemValue>>) {
+    for child in node.children() {
+        let is_scope = child.attr(annotation.scope).is_some();
+        let names = child.attr(annotation.property).unwrap_or_default();
+        if !names.trim().is_empty() {
+            let value = if is_scope {
+                ItemValue::Item(parse_item(child, annotation))
+            } else {
+                ItemValue::Text(get_property_value(&child))
+            };
+            for name in names.split_whitespace() {
+                properties.entry(String::from(get_local_name(name))).or_default().push(value.clone());
+            }
+        }
+        if !is_scope {
+            collect_properties(child, annotation, properties);
+        }
+    }
+}
+
+fn get_property_value(node: &Node) -> String {
+    if let Some(content) = node.attr("content") {
+        return String::from(content.trim());
+    }
+    let value = match node.name() {
+        Some("audio") | Some("embed") | Some("iframe") | Some("img") | Some("source") | Some("track") | Some("video") => node.attr("src"),
+        Some("a") | Some("area") | Some("link") => node.attr("href"),
+        Some("object") => node.attr("data"),
+        Some("data") | Some("meter") => node.attr("value"),
+        Some("time") => node.attr("datetime"),
+        _ => None
+    };
+    match value {
+        Some(v) => String::from(v.trim()),
+        _ => String::from(SPACES_REGEX.replace_all(node.text().trim(), " "))
+    }
+}
+
+/// Returns the first article item, looking into the nested items as well.
+pub fn find_article_item(items: &[Item]) -> Option<&Item> {
+    items.iter().find_map(find_article_in_item)
+}
+
+fn find_article_in_item(item: &Item) -> Option<&Item> {
+    if item.item_type.iter().any(|t| is_article_type(t)) {
+        return Some(item);
+    }
+    item.properties.values()
+        .flatten()
+        .find_map(|value| match value {
+            ItemValue::Item(nested_item) => find_article_in_item(nested_item),
+            _ => None
+        })
+}
+
+/// Reads a textual value out of a property, using the `name` or the `url` of nested items such as
+/// a `Person` or an `ImageObject`.
+pub fn get_item_value_text(value: &ItemValue) -> Option<String> {
+    match value {
+        ItemValue::Text(s) if !s.is_empty() => Some(s.to_owned()),
+        ItemValue::Item(item) => ["name", "url", "contentUrl"].iter()
+            .filter_map(|key| item.properties.get(*key))
+            .flatten()
+            .find_map(get_item_value_text),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_items_nested() {
+        let document = Document::from(r#"<html><body>
+            <div itemscope itemtype="http://schema.org/BlogPosting">
+                <h1 itemprop="headline">  The   headline </h1>
+                <div><span itemprop="author" itemscope itemtype="http://schema.org/Person"><span itemprop="name">Jane Doe</span></span></div>
+                <time itemprop="datePublished" datetime="2019-01-02">January 2nd</time>
+                <img itemprop="image" src="/image.jpg">
+            </div>
+            <div itemscope itemtype="http://schema.org/Organization"><span itemprop="name">ACME</span></div>
+        </body></html>"#);
+        let items = get_items(&document);
+        assert_eq!(items.len(), 2);
+        let item = &items[0];
+        assert_eq!(item.item_type, vec!["http://schema.org/BlogPosting"]);
+        assert_eq!(item.properties["headline"], vec![ItemValue::Text(String::from("The headline"))]);
+        assert_eq!(item.properties["datePublished"], vec![ItemValue::Text(String::from("2019-01-02"))]);
+        assert_eq!(item.properties["image"], vec![ItemValue::Text(String::from("/image.jpg"))]);
+        assert_eq!(get_item_value_text(&item.properties["author"][0]).unwrap(), "Jane Doe");
+        assert!(!item.properties.contains_key("name"));
+    }
+
+    #[test]
+    fn test_get_items_rdfa_bbc() {
+        let document = Document::from(include_str!("sites/bbc.com.html"));
+        let items = get_items(&document);
+        let item = find_article_item(&items).unwrap();
+        assert_eq!(item.item_type, vec!["rnews:NewsItem"]);
+        assert_eq!(item.properties["headline"], vec![ItemValue::Text(String::from("Crunch talks on new Greek bailout"))]);
+    }
+
+    #[test]
+    fn test_find_article_item_dev4510b() {
+        let document = Document::from(include_str!("sites/dev4510b.html"));
+        let items = get_items(&document);
+        let item = find_article_item(&items).unwrap();
+        let authors: Vec<String> = item.properties["author"].iter().filter_map(get_item_value_text).collect();
+        assert_eq!(authors, vec!["ANDREW HIGGINS", "KIMIKO DE FREYTAS-TAMURA", "KATRIN BENNHOLD"]);
+    }
+
+    #[test]
+    fn test_find_article_item_foxnews() {
+        let document = Document::from(include_str!("sites/foxnews.com.html"));
+        let items = get_items(&document);
+        let item = find_article_item(&items).unwrap();
+        assert_eq!(item.properties["headline"], vec![ItemValue::Text(String::from("Party insiders give Clinton early, commanding delegate edge"))]);
+    }
+
+    #[test]
+    fn test_find_article_item_nested() {
+        let document = Document::from(r#"<div itemscope itemtype="http://schema.org/WebPage">
+            <div itemprop="mainEntity" itemscope itemtype="http://schema.org/NewsArticle"><h1 itemprop="headline">Nested</h1></div>
+        </div>"#);
+        let items = get_items(&document);
+        let item = find_article_item(&items).unwrap();
+        assert_eq!(item.item_type, vec!["http://schema.org/NewsArticle"]);
+    }
+}
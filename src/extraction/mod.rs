@@ -1,14 +1,46 @@
 extern crate select;
 
+#[allow(clippy::manual_map, clippy::useless_conversion)]
 mod text;
 mod content;
+mod date;
+mod author;
+mod boilerplate;
+mod json_ld;
+mod language;
+mod markdown;
+mod microdata;
+mod normalization;
+mod resolver;
+mod sanitizer;
+mod segmentation;
+mod image;
 mod predicate;
 mod stopwords;
 
 pub mod extractor {
+    use std::collections::{BTreeMap, HashSet};
+
+    use chrono::{DateTime, Utc};
     use select::document::Document;
+    use select::node::Node;
+    use select::predicate::{Attr, Name, Predicate};
+    use serde_json::Value;
+
+    use url::Url;
 
-    use crate::extraction::content::{get_cleaned_text_and_links, get_top_node};
+    use crate::article::{Article, Block, Image, ImageCandidate, Item, Link, Meta};
+    use crate::configuration::{BoilerplatePatterns, CustomStopwords, HtmlAllowlist, TextNormalization};
+    use crate::extraction::content::{get_cleaned_body_and_links, get_removed_nodes, get_top_node, mark_external_links};
+    use crate::extraction::date::parse_date;
+    use crate::extraction::image;
+    use crate::extraction::language;
+    use crate::extraction::markdown;
+    use crate::extraction::json_ld::get_json_ld_items;
+    use crate::extraction::microdata::get_items;
+    use crate::extraction::resolver::UrlResolver;
+    use crate::extraction::sanitizer;
+    use crate::extraction::stopwords::get_stopwords;
     use crate::extraction::text::*;
 
     pub fn get_text_from_single_extractor<T: TextExtractor>(document: &Document, extractor: T) -> String {
@@ -23,16 +55,43 @@ pub mod extractor {
         get_text_from_single_extractor(document, extractor)
     }
 
-    pub fn get_title(document: &Document) -> String {
-        get_raw_title(document)
+    /// Prefers the JSON-LD headline to the title of the page, and the microdata headline only when
+    /// the title of the page is missing or generic, since page-wide microdata items tend to pick up
+    /// the headlines of sidebars.
+    pub fn get_title(document: &Document, structured_data: &[Value], microdata: &[Item]) -> String {
+        if let Some(headline) = (JsonLdArticleExtractor { items: structured_data, key: "headline" }).extract(document) {
+            return headline;
+        }
+        let raw_title = get_raw_title(document);
+        if !is_generic_title(document, &raw_title) {
+            return raw_title;
+        }
+        match (MicrodataArticleExtractor { items: microdata, key: "headline" }).extract(document) {
+            Some(headline) => headline,
+            _ => raw_title
+        }
+    }
+
+    const SITE_NAME_EXTRACTOR: MetaContentBasedExtractor = MetaContentBasedExtractor { attr: "property", value: "og:site_name" };
+
+    /// Tells whether a title is empty or only names the site.
+    fn is_generic_title(document: &Document, title: &str) -> bool {
+        let title = title.trim();
+        title.is_empty() || SITE_NAME_EXTRACTOR.extract(document).is_some_and(|site_name| site_name.trim().eq_ignore_ascii_case(title))
     }
 
     pub fn get_language(document: &Document) -> String {
-        let full_language = get_text_from_single_extractor(document, LanguageTextExtractor::default());
-        match full_language.find('-') {
-            Some(idx) => String::from(&full_language[..idx]),
-            _ => full_language
-        }
+        language::normalize_language_code(&get_declared_language(document))
+    }
+
+    /// The language tag as declared by the page, such as `pt-BR`, before its normalization.
+    pub fn get_declared_language(document: &Document) -> String {
+        get_text_from_single_extractor(document, LanguageTextExtractor::default()).trim().to_string()
+    }
+
+    /// Identifies the language from the body text, for the pages which do not declare it.
+    pub fn detect_language(document: &Document) -> Option<(String, f64)> {
+        language::detect_language(&language::get_body_text(document))
     }
 
     const LINK_REL_EXTRACTOR: LinkRelContainsHrefBasedExtractor = LinkRelContainsHrefBasedExtractor { attr: "rel", value: " icon" };
@@ -53,17 +112,211 @@ pub mod extractor {
         get_text_from_single_extractor(document, META_KEYWORD_EXTRACTOR)
     }
 
+    const OEMBED_TYPES: [&str; 2] = ["application/json+oembed", "text/json+oembed"];
+
+    pub fn get_oembed_links(document: &Document) -> Vec<String> {
+        document.find(Name("link").and(Attr("rel", "alternate")))
+            .filter(|node| node.attr("type").is_some_and(|t| OEMBED_TYPES.contains(&t)))
+            .filter_map(|node| node.attr("href"))
+            .map(|href| String::from(href.trim()))
+            .filter(|href| !href.is_empty())
+            .collect()
+    }
+
     const TOP_IMAGE_EXTRACTOR: TopImageExtractor = TopImageExtractor;
 
-    pub fn get_top_image(document: &Document) -> String {
-        get_text_from_single_extractor(document, TOP_IMAGE_EXTRACTOR)
+    pub fn get_top_image(document: &Document, structured_data: &[Value], microdata: &[Item]) -> String {
+        let extractor = JsonLdArticleExtractor { items: structured_data, key: "image" }
+            .or(TOP_IMAGE_EXTRACTOR)
+            .or(MicrodataArticleExtractor { items: microdata, key: "image" });
+        get_text_from_single_extractor(document, extractor)
+    }
+
+    pub fn get_structured_data(document: &Document) -> Vec<Value> {
+        get_json_ld_items(document)
+    }
+
+    pub fn get_microdata(document: &Document) -> Vec<Item> {
+        get_items(document)
+    }
+
+    pub fn get_published_date(document: &Document, structured_data: &[Value], microdata: &[Item]) -> (String, Option<DateTime<Utc>>) {
+        let extractor = DateExtractor(JsonLdArticleExtractor { items: structured_data, key: "datePublished" })
+            .or(DateExtractor(MetaContentBasedExtractor { attr: "property", value: "article:published_time" }))
+            .or(DateExtractor(MicrodataArticleExtractor { items: microdata, key: "datePublished" }))
+            .or(DateExtractor(ItempropBasedExtractor { value: "datePublished" }))
+            .or(DateExtractor(JsonLdBasedExtractor { items: structured_data, key: "datePublished" }))
+            .or(DateExtractor(TagAttributeBasedExtractor { tag: "time", attr: "datetime" }))
+            .or(DateExtractor(UrlDateExtractor));
+        get_date(document, extractor)
+    }
+
+    pub fn get_modified_date(document: &Document, structured_data: &[Value], microdata: &[Item]) -> (String, Option<DateTime<Utc>>) {
+        let extractor = DateExtractor(JsonLdArticleExtractor { items: structured_data, key: "dateModified" })
+            .or(DateExtractor(MetaContentBasedExtractor { attr: "property", value: "article:modified_time" }))
+            .or(DateExtractor(MetaContentBasedExtractor { attr: "property", value: "og:updated_time" }))
+            .or(DateExtractor(MicrodataArticleExtractor { items: microdata, key: "dateModified" }))
+            .or(DateExtractor(ItempropBasedExtractor { value: "dateModified" }))
+            .or(DateExtractor(JsonLdBasedExtractor { items: structured_data, key: "dateModified" }));
+        get_date(document, extractor)
+    }
+
+    /// Parses the first date found by the extractors, or keeps the first raw value found when
+    /// none of them can be parsed.
+    fn get_date<T: TextExtractor + RawDateExtractor>(document: &Document, extractor: T) -> (String, Option<DateTime<Utc>>) {
+        match extractor.extract(document) {
+            Some(raw_date) => {
+                let date = parse_date(&raw_date);
+                (raw_date, date)
+            }
+            _ => (extractor.extract_raw(document).unwrap_or_default(), None)
+        }
+    }
+
+    pub fn get_authors(document: &Document, structured_data: &[Value], microdata: &[Item]) -> Vec<String> {
+        let extractor = AuthorsExtractor(JsonLdArticleNamesExtractor { items: structured_data, key: "author" })
+            .or(AuthorsExtractor(MetaContentsBasedExtractor { attr: "name", value: "author" }))
+            .or(AuthorsExtractor(MetaContentsBasedExtractor { attr: "property", value: "article:author" }))
+            .or(AuthorsExtractor(MicrodataArticleNamesExtractor { items: microdata, key: "author" }))
+            .or(AuthorsExtractor(AnchorRelBasedExtractor { value: "author" }))
+            .or(AuthorsExtractor(ItempropNamesExtractor { value: "author" }))
+            .or(AuthorsExtractor(JsonLdNamesExtractor { items: structured_data, key: "author" }))
+            .or(AuthorsExtractor(ClassBasedTextsExtractor { classes: &["byline", "author-name", "author"] }));
+        extractor.extract_all(document)
+    }
+
+    pub fn get_meta(document: &Document) -> Meta {
+        let description_extractor = MetaContentBasedExtractor { attr: "name", value: "description" }
+            .or(MetaContentBasedExtractor { attr: "property", value: "og:description" });
+        Meta {
+            description: get_text_from_single_extractor(document, description_extractor),
+            og_type: get_text_from_single_extractor(document, MetaContentBasedExtractor { attr: "property", value: "og:type" }),
+            og_site_name: get_text_from_single_extractor(document, MetaContentBasedExtractor { attr: "property", value: "og:site_name" }),
+            og_url: get_text_from_single_extractor(document, MetaContentBasedExtractor { attr: "property", value: "og:url" }),
+            og_locale: get_text_from_single_extractor(document, MetaContentBasedExtractor { attr: "property", value: "og:locale" }),
+            twitter_site: get_text_from_single_extractor(document, MetaContentBasedExtractor { attr: "name", value: "twitter:site" }
+                .or(MetaContentBasedExtractor { attr: "property", value: "twitter:site" })),
+            twitter_creator: get_text_from_single_extractor(document, MetaContentBasedExtractor { attr: "name", value: "twitter:creator" }
+                .or(MetaContentBasedExtractor { attr: "property", value: "twitter:creator" })),
+            properties: get_meta_properties(document),
+        }
+    }
+
+    fn get_meta_properties(document: &Document) -> BTreeMap<String, Vec<String>> {
+        let mut properties: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for node in document.find(Name("meta")) {
+            let key = node.attr("property").or_else(|| node.attr("name"));
+            if let (Some(key), Some(content)) = (key, node.attr("content")) {
+                properties.entry(String::from(key)).or_default().push(String::from(content));
+            }
+        }
+        properties
+    }
+
+    const BASE_HREF_EXTRACTOR: TagAttributeBasedExtractor = TagAttributeBasedExtractor { tag: "base", attr: "href" };
+
+    /// Resolves the URL-valued fields of the article against the `<base href>` of the document,
+    /// or the URL of the page when there is none. Unresolvable links are dropped.
+    fn get_base_url(document: &Document, page_url: &Url) -> Url {
+        match BASE_HREF_EXTRACTOR.extract(document) {
+            Some(base_href) => page_url.join(base_href.trim()).unwrap_or_else(|_| page_url.clone()),
+            _ => page_url.clone()
+        }
+    }
+
+    pub fn resolve_urls(document: &Document, page_url: &Url, article: &mut Article) {
+        let resolver = UrlResolver::new(get_base_url(document, page_url));
+        article.favico = resolver.resolve_or_empty(&article.favico);
+        article.canonical_link = resolver.resolve_or_empty(&article.canonical_link);
+        article.top_image = resolver.resolve_or_empty(&article.top_image);
+        for candidate in article.top_image_candidates.iter_mut() {
+            candidate.src = resolver.resolve_or_empty(&candidate.src);
+        }
+        article.meta.og_url = resolver.resolve_or_empty(&article.meta.og_url);
+        let reference_url = resolver.resolve(&get_canonical_link(document)).unwrap_or_else(|| String::from(page_url.as_str()));
+        article.links.retain(|link| resolver.resolve(&link.href).is_some());
+        for link in article.links.iter_mut() {
+            link.href = resolver.resolve_or_empty(&link.href);
+        }
+        mark_external_links(&mut article.links, &reference_url);
+        article.images.retain(|image| resolver.resolve(&image.src).is_some());
+        for image in article.images.iter_mut() {
+            image.src = resolver.resolve_or_empty(&image.src);
+            image.srcset.retain(|candidate| resolver.resolve(&candidate.url).is_some());
+            for candidate in image.srcset.iter_mut() {
+                candidate.url = resolver.resolve_or_empty(&candidate.url);
+            }
+        }
+        article.oembed_links = article.oembed_links.iter().filter_map(|url| resolver.resolve(url)).collect();
+        for embedding in article.embeddings.posts.values_mut().flatten() {
+            embedding.url = resolver.resolve_or_empty(&embedding.url);
+            embedding.media = embedding.media.iter().filter_map(|url| resolver.resolve(url)).collect();
+        }
+        for video in article.embeddings.videos.iter_mut() {
+            video.url = resolver.resolve_or_empty(&video.url);
+            video.thumbnail = resolver.resolve_or_empty(&video.thumbnail);
+        }
+    }
+
+    pub fn get_content_node<'a>(document: &'a Document, lang: &str, custom_stopwords: &BTreeMap<String, CustomStopwords>, patterns: &BoilerplatePatterns) -> Option<Node<'a>> {
+        get_top_node(document, &get_stopwords(lang, custom_stopwords), patterns)
+    }
+
+    /// Lists the nodes of the content node left out of its text and of its Markdown and HTML
+    /// renderings, computed once for all of them.
+    pub fn get_excluded_nodes(content_node: Option<Node>, patterns: &BoilerplatePatterns) -> HashSet<usize> {
+        match content_node {
+            Some(node) => get_removed_nodes(node, patterns),
+            _ => HashSet::new()
+        }
+    }
+
+    pub fn get_body_and_links(document: &Document, content_node: Option<Node>, excluded_nodes: &HashSet<usize>, normalization: &TextNormalization) -> (Vec<Block>, Vec<Link>) {
+        match content_node {
+            Some(node) => {
+                let (body, mut links) = get_cleaned_body_and_links(node, excluded_nodes, normalization);
+                mark_external_links(&mut links, &get_canonical_link(document));
+                (body, links)
+            }
+            _ => (Vec::new(), Vec::new())
+        }
+    }
+
+    /// Renders the content node as CommonMark, with the URLs resolved when the URL of the page is
+    /// known.
+    pub fn get_markdown(document: &Document, content_node: Option<Node>, excluded_nodes: &HashSet<usize>, page_url: Option<&Url>) -> String {
+        match content_node {
+            Some(node) => {
+                let resolver = page_url.map(|page_url| UrlResolver::new(get_base_url(document, page_url)));
+                markdown::get_markdown(node, excluded_nodes, resolver.as_ref())
+            }
+            _ => String::new()
+        }
+    }
+
+    /// Serializes the content node as a sanitized HTML fragment, with the URLs resolved when the
+    /// URL of the page is known.
+    pub fn get_clean_html(document: &Document, content_node: Option<Node>, allowlist: &HtmlAllowlist, excluded_nodes: &HashSet<usize>, page_url: Option<&Url>) -> String {
+        match content_node {
+            Some(node) => {
+                let resolver = page_url.map(|page_url| UrlResolver::new(get_base_url(document, page_url)));
+                sanitizer::get_clean_html(node, allowlist, excluded_nodes, resolver.as_ref())
+            }
+            _ => String::new()
+        }
+    }
+
+    pub fn get_top_image_candidates(content_node: Option<Node>) -> Vec<ImageCandidate> {
+        match content_node {
+            Some(node) => image::get_top_image_candidates(node),
+            _ => Vec::new()
+        }
     }
 
-    pub fn get_text_and_links(document: &Document, lang: &str) -> (String, Vec<String>) {
-        let top_node = get_top_node(document, lang);
-        match top_node {
-            Some(node) => get_cleaned_text_and_links(node, lang),
-            _ => (String::new(), Vec::new())
+    pub fn get_images(content_node: Option<Node>) -> Vec<Image> {
+        match content_node {
+            Some(node) => image::get_images(node),
+            _ => Vec::new()
         }
     }
 
@@ -74,7 +327,7 @@ pub mod extractor {
         #[test]
         fn test_get_title_abcnews() {
             let document = Document::from(include_str!("sites/abcnews.go.com.html"));
-            assert_eq!(get_title(&document), "New Jersey Devils Owner Apologizes After Landing Helicopter in Middle of Kids' Soccer Game Forces Cancellation - ABC News");
+            assert_eq!(get_title(&document, &get_structured_data(&document), &get_microdata(&document)), "New Jersey Devils Owner Apologizes After Landing Helicopter in Middle of Kids' Soccer Game Forces Cancellation - ABC News");
         }
 
         #[test]
@@ -95,6 +348,22 @@ pub mod extractor {
             assert_eq!(get_language(&document), "ja");
         }
 
+        #[test]
+        fn test_get_language_normalized() {
+            let document = Document::from(r#"<html lang="pt-BR"><body></body></html>"#);
+            assert_eq!(get_language(&document), "pt");
+            let document = Document::from(r#"<html><head><meta http-equiv="content-language" content="zh-Hant-TW"></head></html>"#);
+            assert_eq!(get_language(&document), "zh");
+        }
+
+        #[test]
+        fn test_get_declared_language() {
+            let document = Document::from(r#"<html lang=" pt-BR "><body></body></html>"#);
+            assert_eq!(get_declared_language(&document), "pt-BR");
+            let document = Document::from(include_str!("sites/bbc.co.uk.html"));
+            assert_eq!(get_declared_language(&document), "en-GB");
+        }
+
         #[test]
         fn test_get_meta_language_vnexpress() {
             let document = Document::from(include_str!("sites/vnexpress.net.html"));
@@ -112,5 +381,168 @@ pub mod extractor {
             let document = Document::from(include_str!("sites/abcnews.go.com.html"));
             assert_eq!(get_canonical_link(&document), "http://abcnews.go.com/US/nj-devils-owner-apologizes-landing-helicopter-middle-kids/story?id=35155591");
         }
+
+        #[test]
+        fn test_get_oembed_links_youtube() {
+            let document = Document::from(include_str!("sites/youtube.com.html"));
+            assert_eq!(get_oembed_links(&document), vec!["http://www.youtube.com/oembed?url=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3DKO_3Qgib6RQ&format=json"]);
+        }
+
+        #[test]
+        fn test_get_oembed_links_soundcloud() {
+            let document = Document::from(include_str!("sites/soundcloud.com.html"));
+            assert_eq!(get_oembed_links(&document), vec!["https://soundcloud.com/oembed?url=https%3A%2F%2Fsoundcloud.com%2Freplyall%2F18-silence-and-respect&format=json"]);
+        }
+
+        #[test]
+        fn test_get_published_date_theguardian() {
+            let document = Document::from(include_str!("sites/theguardian.com.html"));
+            let (raw_date, date) = get_published_date(&document, &get_structured_data(&document), &get_microdata(&document));
+            assert_eq!(raw_date, "2015-11-13T07:40:07.000Z");
+            assert_eq!(date.unwrap().to_rfc3339(), "2015-11-13T07:40:07+00:00");
+        }
+
+        #[test]
+        fn test_get_published_date_nytimes() {
+            let document = Document::from(include_str!("sites/nytimes.com.html"));
+            let (raw_date, date) = get_published_date(&document, &get_structured_data(&document), &get_microdata(&document));
+            assert_eq!(raw_date, "2015-11-13");
+            assert_eq!(date.unwrap().to_rfc3339(), "2015-11-13T00:00:00+00:00");
+        }
+
+        #[test]
+        fn test_get_published_date_absent() {
+            let document = Document::from(include_str!("sites/example.com.html"));
+            assert_eq!(get_published_date(&document, &get_structured_data(&document), &get_microdata(&document)), (String::new(), None));
+        }
+
+        #[test]
+        fn test_get_published_date_unparsable() {
+            let document = Document::from(r#"<html><head><meta property="article:published_time" content="Friday the 13th"></head></html>"#);
+            assert_eq!(get_published_date(&document, &get_structured_data(&document), &get_microdata(&document)), (String::from("Friday the 13th"), None));
+        }
+
+        #[test]
+        fn test_get_modified_date_theguardian() {
+            let document = Document::from(include_str!("sites/theguardian.com.html"));
+            let (raw_date, date) = get_modified_date(&document, &get_structured_data(&document), &get_microdata(&document));
+            assert_eq!(raw_date, "2015-11-13T14:20:49.789Z");
+            assert_eq!(date.unwrap().to_rfc3339(), "2015-11-13T14:20:49.789+00:00");
+        }
+
+        #[test]
+        fn test_get_authors_theguardian() {
+            let document = Document::from(include_str!("sites/theguardian.com.html"));
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["Press Association"]);
+        }
+
+        #[test]
+        fn test_get_authors_nytimes() {
+            let document = Document::from(include_str!("sites/nytimes.com.html"));
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["Gregor Aisch", "Josh Keller"]);
+        }
+
+        #[test]
+        fn test_get_authors_telegraph() {
+            let document = Document::from(include_str!("sites/telegraph.co.uk.html"));
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["Raziye Akkoc"]);
+        }
+
+        #[test]
+        fn test_get_authors_dailymail() {
+            let document = Document::from(include_str!("sites/dailymail.co.uk.html"));
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["Mail Online Reporter"]);
+        }
+
+        #[test]
+        fn test_get_authors_techcrunch() {
+            let document = Document::from(include_str!("sites/techcrunch.com.html"));
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["Frederic Lardinois"]);
+        }
+
+        #[test]
+        fn test_get_meta_theguardian() {
+            let document = Document::from(include_str!("sites/theguardian.com.html"));
+            let meta = get_meta(&document);
+            assert_eq!(meta.og_type, "article");
+            assert_eq!(meta.og_site_name, "the Guardian");
+            assert_eq!(meta.og_url, "http://www.theguardian.com/uk-news/2015/nov/12/storm-abigail-forces-school-closures-in-scotland");
+            assert_eq!(meta.twitter_site, "@guardian");
+            assert!(!meta.description.is_empty());
+            assert_eq!(meta.properties.get("og:type").unwrap(), &vec![String::from("article")]);
+        }
+
+        #[test]
+        fn test_get_meta_absent() {
+            let document = Document::from("<html><head><title>Title</title></head></html>");
+            assert_eq!(get_meta(&document), Meta::default());
+        }
+
+        #[test]
+        fn test_get_title_json_ld_lemonde() {
+            let document = Document::from(include_str!("sites/lemonde.fr.html"));
+            assert_eq!(get_title(&document, &get_structured_data(&document), &get_microdata(&document)), "La Bajon, l’humour et la colère");
+        }
+
+        #[test]
+        fn test_get_published_date_json_ld_lemonde() {
+            let document = Document::from(include_str!("sites/lemonde.fr.html"));
+            let (raw_date, _) = get_published_date(&document, &get_structured_data(&document), &get_microdata(&document));
+            assert_eq!(raw_date, "2018-09-21T17:15:39+02:00");
+        }
+
+        #[test]
+        fn test_get_authors_json_ld_bbc() {
+            let document = Document::from(include_str!("sites/bbc.co.uk.html"));
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["James Gallagher"]);
+        }
+
+        #[test]
+        fn test_get_top_image_json_ld_techcrunch() {
+            let document = Document::from(include_str!("sites/techcrunch.com.html"));
+            assert_eq!(get_top_image(&document, &get_structured_data(&document), &get_microdata(&document)), "https://tctechcrunch2011.files.wordpress.com/2015/02/gmail-autocomplete.png");
+        }
+
+        #[test]
+        fn test_get_structured_data_figaro() {
+            let document = Document::from(include_str!("sites/figaro.fr.html"));
+            let structured_data = get_structured_data(&document);
+            assert_eq!(structured_data.len(), 3);
+            assert_eq!(structured_data[2]["headline"], "La Fouine contre Booba, Oxmo Puccino arbitre");
+        }
+
+        #[test]
+        fn test_get_title_rdfa_bbc() {
+            let document = Document::from(include_str!("sites/bbc.com.html"));
+            assert_eq!(get_title(&document, &get_structured_data(&document), &get_microdata(&document)), "BBC News - Crunch talks on new Greek bailout under way");
+        }
+
+        #[test]
+        fn test_get_title_microdata_generic_title() {
+            let document = Document::from(r#"<html><head><title>BBC News</title><meta property="og:site_name" content="BBC News"></head>
+                <body><article itemscope itemtype="http://schema.org/NewsArticle"><h1 itemprop="headline">Crunch talks on new Greek bailout</h1></article></body></html>"#);
+            assert_eq!(get_title(&document, &get_structured_data(&document), &get_microdata(&document)), "Crunch talks on new Greek bailout");
+        }
+
+        #[test]
+        fn test_get_title_microdata_foxnews() {
+            let document = Document::from(include_str!("sites/foxnews.com.html"));
+            assert_eq!(get_title(&document, &get_structured_data(&document), &get_microdata(&document)), "Party insiders give Clinton early, commanding delegate edge | Fox News");
+        }
+
+        #[test]
+        fn test_get_authors_microdata() {
+            let document = Document::from(r#"<html><body><article itemscope itemtype="http://schema.org/Article">
+                <p>By <span itemprop="author" itemscope itemtype="http://schema.org/Person"><span itemprop="name">Jane Doe</span></span></p>
+            </article></body></html>"#);
+            assert_eq!(get_authors(&document, &get_structured_data(&document), &get_microdata(&document)), vec!["Jane Doe"]);
+        }
+
+        #[test]
+        fn test_get_microdata_dev4510b() {
+            let document = Document::from(include_str!("sites/dev4510b.html"));
+            let items = get_microdata(&document);
+            assert!(items.iter().any(|item| item.item_type == vec!["http://schema.org/NewsArticle"]));
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,140 @@
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::configuration::TextNormalization;
+
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}')
+}
+
+/// Normalizes the typography of a text node and, unless it belongs to a code block, collapses its
+/// whitespace.
+pub fn normalize_text(text: &str, normalization: &TextNormalization, keep_whitespace: bool) -> String {
+    let mut normalized = if normalization.unicode_nfc { text.nfc().collect() } else { String::from(text) };
+    if normalization.replace_smart_quotes || normalization.remove_soft_hyphens || normalization.remove_zero_width_characters {
+        normalized = normalized.chars()
+            .filter_map(|c| match c {
+                '\u{AD}' if normalization.remove_soft_hyphens => None,
+                c if normalization.remove_zero_width_characters && is_zero_width(c) => None,
+                '‘' | '’' | '‚' | '‛' if normalization.replace_smart_quotes => Some('\''),
+                '“' | '”' | '„' | '‟' if normalization.replace_smart_quotes => Some('"'),
+                c => Some(c)
+            })
+            .collect();
+    }
+    if normalization.collapse_whitespace && !keep_whitespace {
+        normalized = SPACES_REGEX.replace_all(&normalized, " ").into_owned();
+    }
+    normalized
+}
+
+/// Trims the lines of a block, merges its runs of more than two line breaks and trims the block.
+/// The offsets in the normalized text of every character of the raw text, and of its end, are
+/// returned along with it. Only the blank lines at the start and the whitespace at the end of a
+/// code block are removed.
+pub fn normalize_block(text: &str, normalization: &TextNormalization, keep_whitespace: bool) -> (String, Vec<usize>) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut kept: Vec<bool> = vec![true; chars.len()];
+    let is_blank = |c: char| c.is_whitespace() && c != '\n';
+    if normalization.trim_lines && !keep_whitespace {
+        let mut line_start: usize = 0;
+        for line_end in (0..=chars.len()).filter(|&i| i == chars.len() || chars[i] == '\n') {
+            let mut start = line_start;
+            while start < line_end && is_blank(chars[start]) {
+                kept[start] = false;
+                start += 1;
+            }
+            let mut end = line_end;
+            while end > start && is_blank(chars[end - 1]) {
+                kept[end - 1] = false;
+                end -= 1;
+            }
+            line_start = line_end + 1;
+        }
+    }
+    if normalization.merge_newlines && !keep_whitespace {
+        let mut newlines: usize = 0;
+        for (c, kept) in chars.iter().zip(kept.iter_mut()).filter(|(_, kept)| **kept) {
+            if *c == '\n' {
+                newlines += 1;
+                *kept = newlines <= 2;
+            } else {
+                newlines = 0;
+            }
+        }
+    }
+    let is_leading = |c: char| if keep_whitespace { c == '\n' || c == '\r' } else { c.is_whitespace() };
+    for (c, kept) in chars.iter().zip(kept.iter_mut()) {
+        if *kept && !is_leading(*c) {
+            break;
+        }
+        *kept = false;
+    }
+    for (c, kept) in chars.iter().zip(kept.iter_mut()).rev() {
+        if *kept && !c.is_whitespace() {
+            break;
+        }
+        *kept = false;
+    }
+    let mut normalized = String::with_capacity(text.len());
+    let mut offsets: Vec<usize> = Vec::with_capacity(chars.len() + 1);
+    let mut offset: usize = 0;
+    for (c, kept) in chars.iter().zip(kept) {
+        offsets.push(offset);
+        if kept {
+            normalized.push(*c);
+            offset += 1;
+        }
+    }
+    offsets.push(offset);
+    (normalized, offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_text() {
+        let normalization = TextNormalization::default();
+        assert_eq!(normalize_text("A \t\u{A0} text\n   on two lines", &normalization, false), "A text on two lines");
+        assert_eq!(normalize_text("fn main() {\n    run();\n}", &normalization, true), "fn main() {\n    run();\n}");
+        assert_eq!(normalize_text("“Smart” l’e\u{301}te\u{AD}\u{200B}", &normalization, false), "“Smart” l’e\u{301}te\u{AD}\u{200B}");
+    }
+
+    #[test]
+    fn test_normalize_text_typography() {
+        let normalization = TextNormalization {
+            unicode_nfc: true,
+            replace_smart_quotes: true,
+            remove_soft_hyphens: true,
+            remove_zero_width_characters: true,
+            ..TextNormalization::default()
+        };
+        assert_eq!(normalize_text("“Smart” l’e\u{301}te\u{AD}\u{200B}", &normalization, false), "\"Smart\" l'\u{E9}te");
+    }
+
+    #[test]
+    fn test_normalize_block() {
+        let normalization = TextNormalization::default();
+        let (text, offsets) = normalize_block(" \n A line \n\n \n\nanother one \n", &normalization, false);
+        assert_eq!(text, "A line\n\nanother one");
+        assert_eq!(offsets.len(), 29);
+        assert_eq!(offsets[3], 0);
+        assert_eq!(offsets[15], 8);
+        assert_eq!(offsets[28], 19);
+        let (text, _) = normalize_block("\n    indented();\n", &normalization, true);
+        assert_eq!(text, "    indented();");
+    }
+
+    #[test]
+    fn test_normalize_block_disabled() {
+        let normalization = TextNormalization { trim_lines: false, merge_newlines: false, ..TextNormalization::default() };
+        let (text, _) = normalize_block(" A line \n\n\n another one ", &normalization, false);
+        assert_eq!(text, "A line \n\n\n another one");
+    }
+}
@@ -17,7 +17,7 @@ impl<'a> Predicate for AttrContains<&'a str, &'a str> {
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ImageTag;
 
-impl<'a> Predicate for ImageTag {
+impl Predicate for ImageTag {
     fn matches(&self, node: &Node) -> bool {
         match node.name() {
             Some("link") => {
@@ -0,0 +1,115 @@
+use url::Url;
+
+/// Resolves the URLs found in a page against its base URL. Only `http` and `https` URLs are kept,
+/// so that `javascript:`, `mailto:`... links are dropped.
+pub struct UrlResolver {
+    base_url: Url,
+}
+
+impl UrlResolver {
+    pub fn new(base_url: Url) -> Self {
+        UrlResolver { base_url }
+    }
+
+    pub fn resolve(&self, raw_url: &str) -> Option<String> {
+        let raw_url = raw_url.trim();
+        if raw_url.is_empty() {
+            return None;
+        }
+        match self.base_url.join(raw_url) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Some(url.into()),
+            _ => None
+        }
+    }
+
+    pub fn resolve_or_empty(&self, raw_url: &str) -> String {
+        self.resolve(raw_url).unwrap_or_default()
+    }
+}
+
+/// Resolves a URL when a resolver is given. Otherwise the URL is kept as it is written, as long as
+/// it is a relative reference or an `http` or `https` one.
+pub fn resolve_url(resolver: Option<&UrlResolver>, raw_url: &str) -> Option<String> {
+    match resolver {
+        Some(resolver) => resolver.resolve(raw_url),
+        _ => filter_url(raw_url)
+    }
+}
+
+/// Keeps a URL that is relative or uses the `http` or `https` scheme. The scheme is read the way
+/// browsers read it: after removing the control characters, such as tabs and newlines, and the
+/// surrounding spaces, regardless of its case. The URL is returned without those characters.
+pub fn filter_url(raw_url: &str) -> Option<String> {
+    let url: String = raw_url.chars().filter(|c| !c.is_ascii_control()).collect();
+    let url = url.trim_matches(|c: char| c.is_ascii_whitespace());
+    if url.is_empty() {
+        return None;
+    }
+    let scheme = url.split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.'));
+    match scheme {
+        Some(scheme) if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") => None,
+        _ => Some(String::from(url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> UrlResolver {
+        UrlResolver::new(Url::parse("https://www.example.com/news/article.html").unwrap())
+    }
+
+    #[test]
+    fn test_resolve_relative() {
+        assert_eq!(resolver().resolve("/img/foo.jpg").unwrap(), "https://www.example.com/img/foo.jpg");
+        assert_eq!(resolver().resolve("other.html").unwrap(), "https://www.example.com/news/other.html");
+        assert_eq!(resolver().resolve("//cdn.example.com/x.png").unwrap(), "https://cdn.example.com/x.png");
+    }
+
+    #[test]
+    fn test_resolve_absolute() {
+        assert_eq!(resolver().resolve(" http://other.com/page ").unwrap(), "http://other.com/page");
+    }
+
+    #[test]
+    fn test_resolve_dropped() {
+        assert_eq!(resolver().resolve(""), None);
+        assert_eq!(resolver().resolve("javascript:void(0)"), None);
+        assert_eq!(resolver().resolve("mailto:someone@example.com"), None);
+        assert_eq!(resolver().resolve_or_empty("javascript:void(0)"), "");
+    }
+
+    #[test]
+    fn test_filter_url() {
+        assert_eq!(filter_url(" /page?a=b:c ").unwrap(), "/page?a=b:c");
+        assert_eq!(filter_url("other.html#x:y").unwrap(), "other.html#x:y");
+        assert_eq!(filter_url("//cdn.example.com/x.png").unwrap(), "//cdn.example.com/x.png");
+        assert_eq!(filter_url("HTTPS://example.com/").unwrap(), "HTTPS://example.com/");
+        assert_eq!(filter_url("ht\ttp://example.com/").unwrap(), "http://example.com/");
+    }
+
+    #[test]
+    fn test_filter_url_dropped() {
+        assert_eq!(filter_url(""), None);
+        assert_eq!(filter_url(" \t\n"), None);
+        assert_eq!(filter_url("javascript:void(0)"), None);
+        assert_eq!(filter_url("JaVaScRiPt:alert(1)"), None);
+        assert_eq!(filter_url("java\tscript:alert(1)"), None);
+        assert_eq!(filter_url("java\nscript:alert(1)"), None);
+        assert_eq!(filter_url("\u{1}\r\n javascript:alert(1)"), None);
+        assert_eq!(filter_url("VBScript:msgbox(1)"), None);
+        assert_eq!(filter_url("data:text/html;base64,PHNjcmlwdD4="), None);
+        assert_eq!(filter_url("mailto:someone@example.com"), None);
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        assert_eq!(resolve_url(Some(&resolver()), "/page").unwrap(), "https://www.example.com/page");
+        assert_eq!(resolve_url(None, "/page").unwrap(), "/page");
+        assert_eq!(resolve_url(None, "java\tscript:alert(1)"), None);
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+
+use regex::Regex;
+use select::node::Node;
+
+use crate::configuration::HtmlAllowlist;
+use crate::extraction::image::{get_image_src, parse_srcset};
+use crate::extraction::resolver::{resolve_url, UrlResolver};
+
+lazy_static! {
+    static ref SPACES_REGEX: Regex = Regex::new(r"\s+").unwrap();
+}
+
+/// Tags removed with their content, whatever the allowlist.
+const DROPPED_TAGS: [&str; 16] = [
+    "button", "canvas", "embed", "form", "iframe", "input", "link", "meta", "noscript", "object",
+    "script", "select", "style", "svg", "template", "textarea",
+];
+
+const VOID_TAGS: [&str; 3] = ["br", "hr", "img"];
+
+/// Serializes the content of the top node as a HTML fragment, leaving out the removed nodes. Only
+/// the tags and attributes of the allowlist are kept, and the elements left without text, such as
+/// empty paragraphs or links, are dropped. The URLs are resolved when a resolver is given.
+pub fn get_clean_html(node: Node, allowlist: &HtmlAllowlist, excluded_nodes: &HashSet<usize>, resolver: Option<&UrlResolver>) -> String {
+    let sanitizer = HtmlSanitizer { excluded_nodes, allowlist, resolver };
+    let mut html = String::new();
+    for child in node.children() {
+        sanitizer.write_node(child, &mut html, false);
+    }
+    String::from(html.trim())
+}
+
+struct HtmlSanitizer<'a> {
+    excluded_nodes: &'a HashSet<usize>,
+    allowlist: &'a HtmlAllowlist,
+    resolver: Option<&'a UrlResolver>,
+}
+
+impl<'a> HtmlSanitizer<'a> {
+    /// Writes a node and tells whether it has any content, that is some text, an image or a rule.
+    fn write_node(&self, node: Node, out: &mut String, in_pre: bool) -> bool {
+        if self.excluded_nodes.contains(&node.index()) {
+            return false;
+        }
+        if let Some(text) = node.as_text() {
+            if in_pre {
+                out.push_str(&escape_text(text));
+            } else {
+                let text = SPACES_REGEX.replace_all(text, " ");
+                let text = if out.is_empty() || out.ends_with(' ') { text.trim_start() } else { &text };
+                out.push_str(&escape_text(text));
+            }
+            return !text.trim().is_empty();
+        }
+        let name = match node.name() {
+            Some(name) if !DROPPED_TAGS.contains(&name) => name,
+            _ => return false
+        };
+        let in_pre = in_pre || name == "pre";
+        let attributes = self.get_attributes(&node, name);
+        let is_dangling = match name {
+            "a" => !attributes.iter().any(|(attribute, _)| attribute == "href") && self.allowlist.is_allowed_attribute("a", "href"),
+            "img" => !attributes.iter().any(|(attribute, _)| attribute == "src"),
+            _ => false
+        };
+        if !self.allowlist.tags.contains(name) || is_dangling {
+            if name == "img" {
+                return false;
+            }
+            let mut has_content = false;
+            for child in node.children() {
+                has_content |= self.write_node(child, out, in_pre);
+            }
+            return has_content;
+        }
+        if VOID_TAGS.contains(&name) {
+            out.push_str(&format_start_tag(name, &attributes));
+            return name != "br";
+        }
+        let mut inner = String::new();
+        let mut has_content = false;
+        for child in node.children() {
+            has_content |= self.write_node(child, &mut inner, in_pre);
+        }
+        if has_content {
+            out.push_str(&format_start_tag(name, &attributes));
+            out.push_str(&inner);
+            out.push_str(&format!("</{}>", name));
+        }
+        has_content
+    }
+
+    /// Keeps the allowed attributes, with their URLs resolved. The URLs which cannot be resolved,
+    /// such as `javascript:` ones, are dropped along with their attribute.
+    fn get_attributes(&self, node: &Node, name: &str) -> Vec<(String, String)> {
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        if name == "img" && self.allowlist.is_allowed_attribute(name, "src") {
+            if let Some(src) = self.resolve(&get_image_src(node)) {
+                attributes.push((String::from("src"), src));
+            }
+        }
+        for (attribute, value) in node.attrs() {
+            let attribute = attribute.to_lowercase();
+            if attribute.starts_with("on") || attribute == "style" || !self.allowlist.is_allowed_attribute(name, &attribute) {
+                continue;
+            }
+            let value = match attribute.as_str() {
+                "src" if name == "img" => continue,
+                "href" | "src" | "cite" => self.resolve(value),
+                "srcset" => {
+                    let candidates: Vec<String> = parse_srcset(value).iter()
+                        .filter_map(|candidate| self.resolve(&candidate.url)
+                            .map(|url| format!("{} {}", url, candidate.descriptor).trim_end().to_string()))
+                        .collect();
+                    if candidates.is_empty() { None } else { Some(candidates.join(", ")) }
+                }
+                _ => Some(String::from(value.trim()))
+            };
+            if let Some(value) = value {
+                attributes.push((attribute, value));
+            }
+        }
+        attributes
+    }
+
+    fn resolve(&self, raw_url: &str) -> Option<String> {
+        resolve_url(self.resolver, raw_url)
+    }
+}
+
+fn format_start_tag(name: &str, attributes: &[(String, String)]) -> String {
+    let mut tag = format!("<{}", name);
+    for (attribute, value) in attributes {
+        tag.push_str(&format!(" {}=\"{}\"", attribute, escape_attribute(value)));
+    }
+    tag.push('>');
+    tag
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use select::document::Document;
+    use select::predicate::Name;
+    use url::Url;
+
+    use crate::configuration::BoilerplatePatterns;
+    use crate::extraction::content::get_removed_nodes;
+
+    use super::*;
+
+    fn get_div_clean_html(html: &str, allowlist: &HtmlAllowlist, resolver: Option<&UrlResolver>) -> String {
+        let document = Document::from(html);
+        let node = document.find(Name("div")).next().unwrap();
+        get_clean_html(node, allowlist, &get_removed_nodes(node, &BoilerplatePatterns::default()), resolver)
+    }
+
+    #[test]
+    fn test_get_clean_html() {
+        let html = get_div_clean_html(r#"<html><body><div class="content">
+            <h2 id="title" style="color: red">A   heading</h2>
+            <p class="intro" onclick="track()" data-track="1">A paragraph with <em>emphasis</em>, <span class="x">a span</span>
+            and <a href="/page" ping="/track" onmouseover="x()" title="A page">a link</a> &amp; <a href="javascript:void(0)">a script</a>.</p>
+            <p> </p><p><span></span><a href="/empty"></a></p>
+            <blockquote><p>A quote.</p></blockquote>
+            <ul><li>An item</li></ul>
+            <pre><code>fn main() {
+    run();
+}</code></pre>
+            <figure><img src="data:image/gif;base64,R0lGOD" data-src="/a.jpg" alt="An &quot;image&quot;" onerror="x()"><figcaption>A caption</figcaption></figure>
+            <script>var x = 1;</script><style>p {}</style>
+            </div></body></html>"#, &HtmlAllowlist::default(), None);
+        assert_eq!(html, "<h2>A heading</h2>\
+            <p>A paragraph with <em>emphasis</em>, a span and <a href=\"/page\" title=\"A page\">a link</a> &amp; a script.</p>\
+            <blockquote><p>A quote.</p></blockquote>\
+            <ul><li>An item</li></ul>\
+            <pre><code>fn main() {\n    run();\n}</code></pre>\
+            <figure><img src=\"/a.jpg\" alt=\"An &quot;image&quot;\"><figcaption>A caption</figcaption></figure>");
+    }
+
+    #[test]
+    fn test_get_clean_html_allowlist() {
+        let mut allowlist = HtmlAllowlist::default();
+        allowlist.tags.remove("em");
+        allowlist.allow_attribute("p", "class");
+        allowlist.allow_attribute("p", "onclick");
+        let html = get_div_clean_html(r#"<div><p class="intro" onclick="track()" style="color: red">A paragraph with <em>emphasis</em>.</p></div>"#, &allowlist, None);
+        assert_eq!(html, r#"<p class="intro">A paragraph with emphasis.</p>"#);
+    }
+
+    #[test]
+    fn test_get_clean_html_resolved_urls() {
+        let resolver = UrlResolver::new(Url::parse("https://www.example.com/news/article.html").unwrap());
+        let html = get_div_clean_html(r#"<div><p>A <a href="other.html">link</a>, <a href="mailto:me@example.com">a mail</a>
+            and <img src="photo.jpg" srcset="photo-2x.jpg 2x, javascript:x 3x">.</p></div>"#, &HtmlAllowlist::default(), Some(&resolver));
+        assert_eq!(html, r#"<p>A <a href="https://www.example.com/news/other.html">link</a>, a mail and <img src="https://www.example.com/news/photo.jpg" srcset="https://www.example.com/news/photo-2x.jpg 2x">.</p>"#);
+    }
+
+    #[test]
+    fn test_get_clean_html_scripted_urls() {
+        let html = get_div_clean_html(r#"<div><p><a href="java&#x09;script:alert(1)">a tab</a>, <a href="java&#x0A;script:alert(1)">a newline</a>,
+            <a href="JaVaScRiPt:alert(1)">a case</a>, <a href=" &#x01;vbscript:msgbox(1)">a control</a>, <a href="mailto:me@example.com">a mail</a>
+            <img src="data:text/html;base64,PHNjcmlwdD4="> and <a href="/page">a page</a></p></div>"#, &HtmlAllowlist::default(), None);
+        assert_eq!(html, r#"<p>a tab, a newline, a case, a control, a mail and <a href="/page">a page</a></p>"#);
+    }
+}
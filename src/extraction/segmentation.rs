@@ -0,0 +1,107 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Tells whether a character belongs to a script written without spaces between the words: Han,
+/// Hiragana, Katakana and Thai.
+fn is_unspaced(c: char) -> bool {
+    matches!(c as u32,
+        0x0E00..=0x0E7F
+        | 0x3040..=0x30FF
+        | 0x31F0..=0x31FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xF900..=0xFAFF
+        | 0xFF66..=0xFF9F)
+}
+
+/// Splits a text into words. The Unicode word boundaries are used for the scripts separating their
+/// words with spaces. The runs of Han, kana or Thai characters are cut with the dictionary, longest
+/// entry first, and the characters not found in the dictionary are grouped in bigrams, the average
+/// length of a word in these languages. The dictionary must be sorted.
+pub fn get_words<'a>(text: &'a str, dictionary: &[&str]) -> Vec<&'a str> {
+    let mut words: Vec<&'a str> = Vec::new();
+    let mut run: Option<(usize, usize)> = None;
+    for (start, word) in text.unicode_word_indices() {
+        let end = start + word.len();
+        if word.chars().all(is_unspaced) {
+            run = match run {
+                Some((run_start, run_end)) if run_end == start => Some((run_start, end)),
+                Some((run_start, run_end)) => {
+                    segment_run(&text[run_start..run_end], dictionary, &mut words);
+                    Some((start, end))
+                }
+                _ => Some((start, end))
+            };
+        } else {
+            if let Some((run_start, run_end)) = run.take() {
+                segment_run(&text[run_start..run_end], dictionary, &mut words);
+            }
+            words.push(word);
+        }
+    }
+    if let Some((run_start, run_end)) = run {
+        segment_run(&text[run_start..run_end], dictionary, &mut words);
+    }
+    words
+}
+
+fn segment_run<'a>(run: &'a str, dictionary: &[&str], words: &mut Vec<&'a str>) {
+    let max_entry_length = dictionary.iter().map(|entry| entry.graphemes(true).count()).max().unwrap_or(0);
+    let boundaries: Vec<usize> = run.grapheme_indices(true).map(|(idx, _)| idx).chain(Some(run.len())).collect();
+    let mut unknown_start: Option<usize> = None;
+    let mut i: usize = 0;
+    while i + 1 < boundaries.len() {
+        let longest_match = (1..=max_entry_length.min(boundaries.len() - 1 - i)).rev()
+            .find(|length| dictionary.binary_search(&&run[boundaries[i]..boundaries[i + length]]).is_ok());
+        match longest_match {
+            Some(length) => {
+                if let Some(unknown_start) = unknown_start.take() {
+                    push_bigrams(&run[boundaries[unknown_start]..boundaries[i]], words);
+                }
+                words.push(&run[boundaries[i]..boundaries[i + length]]);
+                i += length;
+            }
+            _ => {
+                unknown_start.get_or_insert(i);
+                i += 1;
+            }
+        }
+    }
+    if let Some(unknown_start) = unknown_start {
+        push_bigrams(&run[boundaries[unknown_start]..], words);
+    }
+}
+
+fn push_bigrams<'a>(text: &'a str, words: &mut Vec<&'a str>) {
+    let boundaries: Vec<usize> = text.grapheme_indices(true).map(|(idx, _)| idx).chain(Some(text.len())).collect();
+    let mut i: usize = 0;
+    while i + 1 < boundaries.len() {
+        let end = boundaries[(i + 2).min(boundaries.len() - 1)];
+        words.push(&text[boundaries[i]..end]);
+        i += 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_words_spaced() {
+        assert_eq!(get_words("I live in London, in England", &[]), vec!["I", "live", "in", "London", "in", "England"]);
+    }
+
+    #[test]
+    fn test_get_words_bigrams() {
+        assert_eq!(get_words("东京大学", &[]), vec!["东京", "大学"]);
+        assert_eq!(get_words("深海生物", &[]).len(), 2);
+        assert_eq!(get_words("Tokyo 東京都 2015", &[]), vec!["Tokyo", "東京", "都", "2015"]);
+    }
+
+    #[test]
+    fn test_get_words_dictionary() {
+        let dictionary = ["これ", "の", "は"];
+        assert_eq!(get_words("これは日本語の文章", &dictionary), vec!["これ", "は", "日本", "語", "の", "文章"]);
+        let dictionary = ["ที่", "และ"];
+        assert_eq!(get_words("แมวและหมา", &dictionary), vec!["แม", "ว", "และ", "หม", "า"]);
+    }
+}
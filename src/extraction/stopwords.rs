@@ -3,23 +3,43 @@ extern crate stopwords;
 use std::collections::BTreeMap;
 
 use stopwords::{Language, NLTK, Stopwords};
-use unicode_segmentation::UnicodeSegmentation;
+
+use crate::configuration::{CustomStopwords, StopwordsMode};
+use crate::extraction::language::normalize_language_code;
+use crate::extraction::segmentation::get_words;
 
 lazy_static! {
 
     static ref LANGUAGE_STOPWORDS_MAP: BTreeMap< &'static str, Vec<&'static str>> = {
         let mut m = BTreeMap::new();
-        m.insert("en", stopwords_from_language(Language::English));
-        m.insert("fr", stopwords_from_language(Language::French));
+        m.insert("ar", stopwords_from_language(Language::Arabic));
+        m.insert("az", stopwords_from_language(Language::Azerbaijani));
+        m.insert("da", stopwords_from_language(Language::Danish));
         m.insert("de", stopwords_from_language(Language::German));
+        m.insert("el", stopwords_from_language(Language::Greek));
+        m.insert("en", stopwords_from_language(Language::English));
         m.insert("es", stopwords_from_language(Language::Spanish));
-        m.insert("sw", stopwords_from_language(Language::Swedish));
+        m.insert("fi", stopwords_from_language(Language::Finnish));
+        m.insert("fr", stopwords_from_language(Language::French));
+        m.insert("hu", stopwords_from_language(Language::Hungarian));
         m.insert("it", stopwords_from_language(Language::Italian));
+        m.insert("kk", stopwords_from_language(Language::Kazakh));
+        m.insert("ne", stopwords_from_language(Language::Nepali));
+        m.insert("nl", stopwords_from_language(Language::Dutch));
+        m.insert("no", stopwords_from_language(Language::Norwegian));
         m.insert("pt", stopwords_from_language(Language::Portuguese));
+        m.insert("ro", stopwords_from_language(Language::Romanian));
         m.insert("ru", stopwords_from_language(Language::Russian));
-        m.insert("nl", stopwords_from_language(Language::Dutch));
-        m.insert("fi", stopwords_from_language(Language::Finnish));
-       
+        m.insert("sv", stopwords_from_language(Language::Swedish));
+        m.insert("tr", stopwords_from_language(Language::Turkish));
+        m.insert("cs", stopwords_from_list(include_str!("stopwords/cs.txt")));
+        m.insert("he", stopwords_from_list(include_str!("stopwords/he.txt")));
+        m.insert("id", stopwords_from_list(include_str!("stopwords/id.txt")));
+        m.insert("pl", stopwords_from_list(include_str!("stopwords/pl.txt")));
+        m.insert("zh", stopwords_from_list(include_str!("stopwords/zh.txt")));
+        m.insert("ja", stopwords_from_list(include_str!("stopwords/ja.txt")));
+        m.insert("ko", stopwords_from_list(include_str!("stopwords/ko.txt")));
+        m.insert("th", stopwords_from_list(include_str!("stopwords/th.txt")));
         m
     };
 }
@@ -36,24 +56,57 @@ fn stopwords_from_language(lang: Language) -> Vec<&'static str> {
     }
 }
 
-fn get_stopwords_from_language(lang: &str) -> Vec<&'static str> {
-    match LANGUAGE_STOPWORDS_MAP.get(lang) {
+pub fn get_stopword_lists() -> &'static BTreeMap<&'static str, Vec<&'static str>> {
+    &LANGUAGE_STOPWORDS_MAP
+}
+
+/// Reads a newline-delimited list of stopwords.
+fn stopwords_from_list(list: &'static str) -> Vec<&'static str> {
+    let mut stopwords: Vec<&'static str> = list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    stopwords.sort();
+    stopwords.dedup();
+    stopwords
+}
+
+pub fn get_stopwords_from_language(lang: &str) -> Vec<&'static str> {
+    match LANGUAGE_STOPWORDS_MAP.get(normalize_language_code(lang).as_str()) {
         Some(sw) => sw.to_vec(),
         _ => Vec::default()
     }
 }
 
+/// Returns the sorted stopwords of a language, with the custom set registered for the language
+/// merged into or replacing the built-in list.
+pub fn get_stopwords<'a>(lang: &str, custom_stopwords: &'a BTreeMap<String, CustomStopwords>) -> Vec<&'a str> {
+    let lang = normalize_language_code(lang);
+    let custom = custom_stopwords.iter()
+        .find(|(custom_lang, _)| normalize_language_code(custom_lang) == lang)
+        .map(|(_, custom)| custom);
+    let mut stopwords: Vec<&'a str> = match custom {
+        Some(custom) if custom.mode == StopwordsMode::Replace => Vec::new(),
+        _ => get_stopwords_from_language(&lang)
+    };
+    if let Some(custom) = custom {
+        stopwords.extend(custom.words.iter().map(String::as_str));
+        stopwords.sort();
+        stopwords.dedup();
+    }
+    stopwords
+}
+
+/// Counts the stopwords of a text, up to `n` + 1. The stopwords must be sorted.
 #[inline(always)]
-fn count_max_stopwords(text: &str, _lang: &str, n: usize) -> usize {
-    let lowercased_text = text.to_ascii_lowercase();
-    let unicode_words = lowercased_text.unicode_words();
-    let stopwords: Vec<_> = get_stopwords_from_language(_lang);
+fn count_max_stopwords(text: &str, stopwords: &[&str], n: usize) -> usize {
+    let lowercased_text = text.to_lowercase();
     let mut nb_stopwords: usize = 0;
-    for word in unicode_words.into_iter() {
-        let result = stopwords.binary_search(&&word);
+    for word in get_words(&lowercased_text, stopwords) {
+        let result = stopwords.binary_search(&word);
         if result.is_ok() {
             nb_stopwords += 1;
-            if nb_stopwords > (n) as usize {
+            if nb_stopwords > n {
                 return nb_stopwords;
             }
         }
@@ -61,11 +114,11 @@ fn count_max_stopwords(text: &str, _lang: &str, n: usize) -> usize {
     nb_stopwords
 }
 
-pub fn count_stopwords(text: &str, lang: &str) -> usize {
-    count_max_stopwords(text, lang, 999_999)
+pub fn count_stopwords(text: &str, stopwords: &[&str]) -> usize {
+    count_max_stopwords(text, stopwords, 999_999)
 }
 
-pub fn has_more_stopwords_than(text: &str, lang: &str, n: usize) -> bool {
-    let number_of_stopwords = count_max_stopwords(text, lang, n);
+pub fn has_more_stopwords_than(text: &str, stopwords: &[&str], n: usize) -> bool {
+    let number_of_stopwords = count_max_stopwords(text, stopwords, n);
     number_of_stopwords >= n
 }
@@ -1,11 +1,23 @@
 use std::collections::BTreeMap;
 use std::string::String;
 
+use regex::Regex;
 use select::document::Document;
-use select::predicate::{Attr, Name, Predicate};
-
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use serde_json::Value;
+
+use crate::article::Item;
+use crate::extraction::author::normalize_authors;
+use crate::extraction::date::parse_date;
+use crate::extraction::json_ld::{get_article_item, get_json_text};
+use crate::extraction::microdata::{find_article_item, get_item_value_text};
 use crate::extraction::predicate::{AttrContains, ImageTag};
 
+lazy_static! {
+    static ref URL_DATE_REGEX: Regex = Regex::new(r"/((?:19|20)\d{2}/[01]?\d/[0-3]?\d)/").unwrap();
+}
+
 pub trait TextExtractor: Sized {
     fn extract(&self, document: &Document) -> Option<String>;
     fn or<T: TextExtractor>(self, other: T) -> OrExtractor<Self, T>
@@ -171,6 +183,295 @@ impl TextExtractor for TopImageExtractor {
     }
 }
 
+#[derive(Debug)]
+pub struct ItempropBasedExtractor {
+    pub value: &'static str,
+}
+
+impl TextExtractor for ItempropBasedExtractor {
+    fn extract(&self, document: &Document) -> Option<String> {
+        match document.find(Attr("itemprop", self.value)).next() {
+            Some(node) => match node.attr("content").or_else(|| node.attr("datetime")) {
+                Some(s) => Some(String::from(s)),
+                _ => Some(node.text())
+            },
+            _ => None
+        }
+    }
+}
+
+/// Reads the first string value of a key, at any depth, in the parsed JSON-LD items.
+#[derive(Debug)]
+pub struct JsonLdBasedExtractor<'a> {
+    pub items: &'a [Value],
+    pub key: &'static str,
+}
+
+impl<'a> TextExtractor for JsonLdBasedExtractor<'a> {
+    fn extract(&self, _document: &Document) -> Option<String> {
+        self.items.iter()
+            .find_map(|value| find_json_string(value, self.key))
+    }
+}
+
+fn find_json_string(value: &Value, key: &str) -> Option<String> {
+    match value {
+        Value::Object(map) => match map.get(key) {
+            Some(Value::String(s)) => Some(s.to_owned()),
+            _ => map.values().find_map(|v| find_json_string(v, key))
+        },
+        Value::Array(values) => values.iter().find_map(|v| find_json_string(v, key)),
+        _ => None
+    }
+}
+
+/// Reads a property of the first `Article`, `NewsArticle`, `BlogPosting`... JSON-LD item.
+#[derive(Debug)]
+pub struct JsonLdArticleExtractor<'a> {
+    pub items: &'a [Value],
+    pub key: &'static str,
+}
+
+impl<'a> TextExtractor for JsonLdArticleExtractor<'a> {
+    fn extract(&self, _document: &Document) -> Option<String> {
+        match get_article_item(self.items) {
+            Some(item) => item.get(self.key).and_then(get_json_text),
+            _ => None
+        }
+    }
+}
+
+/// Reads a property of the first article microdata or RDFa item.
+#[derive(Debug)]
+pub struct MicrodataArticleExtractor<'a> {
+    pub items: &'a [Item],
+    pub key: &'static str,
+}
+
+impl<'a> TextExtractor for MicrodataArticleExtractor<'a> {
+    fn extract(&self, _document: &Document) -> Option<String> {
+        match find_article_item(self.items).and_then(|item| item.properties.get(self.key)) {
+            Some(values) => values.iter().find_map(get_item_value_text),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UrlDateExtractor;
+
+impl TextExtractor for UrlDateExtractor {
+    fn extract(&self, document: &Document) -> Option<String> {
+        let url_extractor = LinkRelEqualsHrefBasedExtractor { attr: "rel", value: "canonical" }
+            .or(MetaContentBasedExtractor { attr: "property", value: "og:url" });
+        match url_extractor.extract(document) {
+            Some(url) => URL_DATE_REGEX.captures(&url).map(|c| String::from(&c[1])),
+            _ => None
+        }
+    }
+}
+
+/// Only keeps the value of the delegate extractor when it can be parsed as a date, so that the
+/// next extractor of an `OrExtractor` chain is tried otherwise.
+#[derive(Debug)]
+pub struct DateExtractor<T>(pub T);
+
+impl<T: TextExtractor> TextExtractor for DateExtractor<T> {
+    fn extract(&self, document: &Document) -> Option<String> {
+        match self.0.extract(document) {
+            Some(raw) => {
+                let raw = raw.trim();
+                parse_date(raw).map(|_| String::from(raw))
+            }
+            _ => None
+        }
+    }
+}
+
+/// Reads the first value of a chain of `DateExtractor`s, whether it can be parsed as a date or not.
+pub trait RawDateExtractor {
+    fn extract_raw(&self, document: &Document) -> Option<String>;
+}
+
+impl<T: TextExtractor> RawDateExtractor for DateExtractor<T> {
+    fn extract_raw(&self, document: &Document) -> Option<String> {
+        self.0.extract(document)
+            .map(|raw| String::from(raw.trim()))
+            .filter(|raw| !raw.is_empty())
+    }
+}
+
+impl<A: RawDateExtractor, B: RawDateExtractor> RawDateExtractor for OrExtractor<A, B> {
+    fn extract_raw(&self, document: &Document) -> Option<String> {
+        self.0.extract_raw(document).or_else(|| self.1.extract_raw(document))
+    }
+}
+
+pub trait TextsExtractor: Sized {
+    fn extract_all(&self, document: &Document) -> Vec<String>;
+    fn or<T: TextsExtractor>(self, other: T) -> OrTextsExtractor<Self, T>
+        where Self: Sized, { OrTextsExtractor(self, other) }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrTextsExtractor<A, B>(pub A, pub B);
+
+impl<A: TextsExtractor, B: TextsExtractor> TextsExtractor for OrTextsExtractor<A, B> {
+    fn extract_all(&self, document: &Document) -> Vec<String> {
+        let texts = self.0.extract_all(document);
+        if !texts.is_empty() {
+            return texts;
+        }
+        self.1.extract_all(document)
+    }
+}
+
+#[derive(Debug)]
+pub struct MetaContentsBasedExtractor {
+    pub attr: &'static str,
+    pub value: &'static str,
+}
+
+impl TextsExtractor for MetaContentsBasedExtractor {
+    fn extract_all(&self, document: &Document) -> Vec<String> {
+        document.find(Name("meta").and(Attr(self.attr, self.value)))
+            .filter_map(|node| node.attr("content").map(String::from))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct AnchorRelBasedExtractor {
+    pub value: &'static str,
+}
+
+impl TextsExtractor for AnchorRelBasedExtractor {
+    fn extract_all(&self, document: &Document) -> Vec<String> {
+        document.find(Name("a").and(AttrContains("rel", self.value)))
+            .map(|node| node.text())
+            .collect()
+    }
+}
+
+/// Reads the `itemprop="name"` of nested items, e.g. a `Person` marked as the `author`, and falls
+/// back on the `content` attribute or the text of the node.
+#[derive(Debug)]
+pub struct ItempropNamesExtractor {
+    pub value: &'static str,
+}
+
+impl TextsExtractor for ItempropNamesExtractor {
+    fn extract_all(&self, document: &Document) -> Vec<String> {
+        document.find(AttrContains("itemprop", self.value))
+            .map(|node| {
+                let name_node = node.find(Attr("itemprop", "name")).next().unwrap_or(node);
+                match name_node.attr("content") {
+                    Some(s) => String::from(s),
+                    _ => name_node.text()
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonLdNamesExtractor<'a> {
+    pub items: &'a [Value],
+    pub key: &'static str,
+}
+
+impl<'a> TextsExtractor for JsonLdNamesExtractor<'a> {
+    fn extract_all(&self, _document: &Document) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for value in self.items.iter() {
+            collect_json_names(value, self.key, &mut names);
+        }
+        names
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonLdArticleNamesExtractor<'a> {
+    pub items: &'a [Value],
+    pub key: &'static str,
+}
+
+impl<'a> TextsExtractor for JsonLdArticleNamesExtractor<'a> {
+    fn extract_all(&self, _document: &Document) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        if let Some(named) = get_article_item(self.items).and_then(|item| item.get(self.key)) {
+            push_json_names(named, &mut names);
+        }
+        names
+    }
+}
+
+#[derive(Debug)]
+pub struct MicrodataArticleNamesExtractor<'a> {
+    pub items: &'a [Item],
+    pub key: &'static str,
+}
+
+impl<'a> TextsExtractor for MicrodataArticleNamesExtractor<'a> {
+    fn extract_all(&self, _document: &Document) -> Vec<String> {
+        match find_article_item(self.items).and_then(|item| item.properties.get(self.key)) {
+            Some(values) => values.iter().filter_map(get_item_value_text).collect(),
+            _ => Vec::new()
+        }
+    }
+}
+
+fn collect_json_names(value: &Value, key: &str, names: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            match map.get(key) {
+                Some(named) => push_json_names(named, names),
+                _ => map.values().for_each(|v| collect_json_names(v, key, names))
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|v| collect_json_names(v, key, names)),
+        _ => ()
+    }
+}
+
+fn push_json_names(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::String(s) => names.push(s.to_owned()),
+        Value::Object(map) => {
+            if let Some(Value::String(s)) = map.get("name") {
+                names.push(s.to_owned());
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|v| push_json_names(v, names)),
+        _ => ()
+    }
+}
+
+#[derive(Debug)]
+pub struct ClassBasedTextsExtractor {
+    pub classes: &'static [&'static str],
+}
+
+impl TextsExtractor for ClassBasedTextsExtractor {
+    fn extract_all(&self, document: &Document) -> Vec<String> {
+        let classes = self.classes;
+        document.find(|node: &Node| classes.iter().any(|class| Class(*class).matches(node)))
+            .map(|node| node.text())
+            .collect()
+    }
+}
+
+/// Normalizes the names found by the delegate extractor and only keeps them when at least one is
+/// left, so that the next extractor of an `OrTextsExtractor` chain is tried otherwise.
+#[derive(Debug)]
+pub struct AuthorsExtractor<T>(pub T);
+
+impl<T: TextsExtractor> TextsExtractor for AuthorsExtractor<T> {
+    fn extract_all(&self, document: &Document) -> Vec<String> {
+        normalize_authors(&self.0.extract_all(document))
+    }
+}
+
 pub struct LanguageTextExtractor {
     delegate: OrExtractor<TagAttributeBasedExtractor, MetaContentBasedExtractor>,
 }
@@ -193,6 +494,9 @@ impl TextExtractor for LanguageTextExtractor {
 mod tests {
     use select::document::Document;
 
+    use crate::extraction::json_ld::get_json_ld_items;
+    use crate::extraction::microdata::get_items;
+
     use super::*;
 
     #[test]
@@ -266,4 +570,114 @@ mod tests {
         assert!(opt.is_some());
         assert_eq!(opt.unwrap(), "http://3.bp.blogspot.com/-6SCcCupadL0/VUnQdhs_98I/AAAAAAAAA7Q/wCdIXm6v9Sg/s540/Screen%2BShot%2B2015-05-06%2Bat%2B10.22.08%2BAM.png");
     }
+
+    #[test]
+    fn extract_with_itemprop_telegraph() {
+        let document = Document::from(include_str!("sites/telegraph.co.uk.html"));
+        let extractor = ItempropBasedExtractor { value: "datePublished" };
+        assert_eq!(extractor.extract(&document).unwrap(), "2016-06-24T03:35+0100");
+    }
+
+    #[test]
+    fn extract_with_json_ld_lemonde() {
+        let document = Document::from(include_str!("sites/lemonde.fr.html"));
+        let items = get_json_ld_items(&document);
+        let extractor = JsonLdBasedExtractor { items: &items, key: "datePublished" };
+        assert_eq!(extractor.extract(&document).unwrap(), "2018-09-21T17:15:39+02:00");
+    }
+
+    #[test]
+    fn extract_date_from_url() {
+        let document = Document::from("<html><head><link rel=\"canonical\" href=\"http://www.example.com/politics/2016/06/24/brexit/\"></head></html>");
+        assert_eq!(UrlDateExtractor.extract(&document).unwrap(), "2016/06/24");
+    }
+
+    #[test]
+    fn extract_date_skips_unparsable_values() {
+        let document = Document::from(include_str!("sites/dailymail.co.uk.html"));
+        let extractor = DateExtractor(MetaContentBasedExtractor { attr: "property", value: "article:published_time" })
+            .or(DateExtractor(ItempropBasedExtractor { value: "datePublished" }));
+        assert_eq!(extractor.extract(&document).unwrap(), "2015-11-13T10:15:18+0000");
+    }
+
+    #[test]
+    fn extract_raw_date_unparsable() {
+        let document = Document::from(r#"<html><head><meta property="article:published_time" content=" Friday the 13th "></head></html>"#);
+        let extractor = DateExtractor(MetaContentBasedExtractor { attr: "property", value: "article:published_time" })
+            .or(DateExtractor(ItempropBasedExtractor { value: "datePublished" }));
+        assert!(extractor.extract(&document).is_none());
+        assert_eq!(extractor.extract_raw(&document).unwrap(), "Friday the 13th");
+    }
+
+    #[test]
+    fn extract_all_with_meta_nytimes() {
+        let document = Document::from(include_str!("sites/nytimes.com.html"));
+        let extractor = MetaContentsBasedExtractor { attr: "name", value: "author" };
+        assert_eq!(extractor.extract_all(&document), vec!["Gregor Aisch and Josh Keller"]);
+    }
+
+    #[test]
+    fn extract_all_with_itemprop_telegraph() {
+        let document = Document::from(include_str!("sites/telegraph.co.uk.html"));
+        let extractor = ItempropNamesExtractor { value: "author" };
+        assert_eq!(extractor.extract_all(&document).first().unwrap(), "Raziye Akkoc");
+    }
+
+    #[test]
+    fn extract_all_with_json_ld_techcrunch() {
+        let document = Document::from(include_str!("sites/techcrunch.com.html"));
+        let items = get_json_ld_items(&document);
+        let extractor = JsonLdNamesExtractor { items: &items, key: "author" };
+        assert_eq!(extractor.extract_all(&document), vec!["Frederic Lardinois"]);
+    }
+
+    #[test]
+    fn extract_all_authors_skips_empty_values() {
+        let document = Document::from(include_str!("sites/theguardian.com.html"));
+        let extractor = AuthorsExtractor(MetaContentsBasedExtractor { attr: "property", value: "article:author" })
+            .or(AuthorsExtractor(ClassBasedTextsExtractor { classes: &["byline"] }));
+        assert_eq!(extractor.extract_all(&document), vec!["Press Association"]);
+    }
+
+    #[test]
+    fn extract_with_json_ld_article_lemonde() {
+        let document = Document::from(include_str!("sites/lemonde.fr.html"));
+        let items = get_json_ld_items(&document);
+        let extractor = JsonLdArticleExtractor { items: &items, key: "headline" };
+        assert_eq!(extractor.extract(&document).unwrap(), "La Bajon, l’humour et la colère");
+        let extractor = JsonLdArticleExtractor { items: &items, key: "image" };
+        assert_eq!(extractor.extract(&document).unwrap(), "https://img.lemde.fr/2018/09/21/1311/43/5547/2773/696/348/60/0/4288969_1Jtn8HGqjjYrQ51gysSqTkb5.jpg");
+    }
+
+    #[test]
+    fn extract_with_json_ld_article_absent() {
+        let document = Document::from(include_str!("sites/theguardian.com.html"));
+        let items = get_json_ld_items(&document);
+        let extractor = JsonLdArticleExtractor { items: &items, key: "headline" };
+        assert!(extractor.extract(&document).is_none());
+    }
+
+    #[test]
+    fn extract_all_with_json_ld_article_figaro() {
+        let document = Document::from(include_str!("sites/figaro.fr.html"));
+        let items = get_json_ld_items(&document);
+        let extractor = JsonLdArticleNamesExtractor { items: &items, key: "author" };
+        assert_eq!(extractor.extract_all(&document), vec!["Fiona Ipert"]);
+    }
+
+    #[test]
+    fn extract_with_microdata_article_bbc() {
+        let document = Document::from(include_str!("sites/bbc.com.html"));
+        let items = get_items(&document);
+        let extractor = MicrodataArticleExtractor { items: &items, key: "datePublished" };
+        assert_eq!(extractor.extract(&document).unwrap(), "2015/02/20 15:42:28");
+    }
+
+    #[test]
+    fn extract_all_with_microdata_article_dev4510b() {
+        let document = Document::from(include_str!("sites/dev4510b.html"));
+        let items = get_items(&document);
+        let extractor = MicrodataArticleNamesExtractor { items: &items, key: "author" };
+        assert_eq!(extractor.extract_all(&document).len(), 3);
+    }
 }
\ No newline at end of file
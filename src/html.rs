@@ -2,88 +2,202 @@ use chardet::{charset2encoding, detect};
 use encoding::DecoderTrap;
 use encoding::label::encoding_from_whatwg_label;
 use select::document::Document;
+use url::Url;
 
-use crate::article::{Article, Embeddings};
+use crate::article::{Article, Embeddings, get_blocks_text};
 use crate::configuration::Configuration;
 use crate::embedding::*;
+use crate::error::ExtractionError;
 use crate::extraction::extractor::*;
 
-pub struct HtmlExtractor {
-    pub configuration: Configuration,
+fn parse_url(url: &str) -> Result<Url, ExtractionError> {
+    Url::parse(url).map_err(|_| ExtractionError::InvalidUrl(String::from(url)))
 }
 
-impl Default for HtmlExtractor {
-    fn default() -> Self { HtmlExtractor { configuration: Configuration::default() } }
+#[derive(Default)]
+pub struct HtmlExtractor {
+    pub configuration: Configuration,
+    /// Providers run in addition to the built-in ones. A provider replaces the built-in provider
+    /// with the same name.
+    pub providers: Vec<Box<dyn EmbedProvider>>,
+    /// Enriches the embedded posts with their oEmbed data. Without a resolver the extraction never
+    /// leaves the document.
+    pub oembed_resolver: Option<Box<dyn OEmbedResolver>>,
 }
 
 impl HtmlExtractor {
-    pub fn from_string(self: &Self, raw_html: String) -> Option<Article> {
-        let option = self.pre_process(raw_html);
-        match option {
-            Some(document) => self.process(&document, &self.configuration),
-            _ => None
+    pub fn register_provider<P: EmbedProvider + 'static>(&mut self, provider: P) {
+        self.providers.push(Box::new(provider));
+    }
+
+    pub fn set_oembed_resolver<R: OEmbedResolver + 'static>(&mut self, resolver: R) {
+        self.oembed_resolver = Some(Box::new(resolver));
+    }
+
+    pub fn from_string(&self, raw_html: String) -> Result<Article, ExtractionError> {
+        let document = self.pre_process(raw_html)?;
+        let mut article = self.process(&document, &self.configuration, None)?;
+        self.enrich_embeddings(&mut article);
+        Ok(article)
+    }
+
+    /// Same as `from_string`, with every URL of the article resolved against the URL of the page
+    /// or its `<base href>`.
+    pub fn from_string_with_url(&self, raw_html: String, url: &str) -> Result<Article, ExtractionError> {
+        let page_url = parse_url(url)?;
+        let document = self.pre_process(raw_html)?;
+        let mut article = self.process(&document, &self.configuration, Some(&page_url))?;
+        resolve_urls(&document, &page_url, &mut article);
+        self.enrich_embeddings(&mut article);
+        Ok(article)
+    }
+
+    pub fn from_bytes(&self, bytes: Vec<u8>) -> Result<Article, ExtractionError> {
+        let document = self.decode(bytes)?;
+        let mut article = self.process(&document, &self.configuration, None)?;
+        self.enrich_embeddings(&mut article);
+        Ok(article)
+    }
+
+    /// Same as `from_bytes`, with every URL of the article resolved against the URL of the page
+    /// or its `<base href>`.
+    pub fn from_bytes_with_url(&self, bytes: Vec<u8>, url: &str) -> Result<Article, ExtractionError> {
+        let page_url = parse_url(url)?;
+        let document = self.decode(bytes)?;
+        let mut article = self.process(&document, &self.configuration, Some(&page_url))?;
+        resolve_urls(&document, &page_url, &mut article);
+        self.enrich_embeddings(&mut article);
+        Ok(article)
+    }
+
+    fn enrich_embeddings(&self, article: &mut Article) {
+        if let Some(resolver) = &self.oembed_resolver {
+            for embedding in article.embeddings.posts.values_mut().flatten() {
+                if let Some(oembed) = resolver.resolve(&embedding.url) {
+                    embedding.enrich(&oembed);
+                }
+            }
         }
     }
 
-    pub fn from_bytes(self: &Self, bytes: Vec<u8>) -> Option<Article> {
+    fn decode(&self, bytes: Vec<u8>) -> Result<Document, ExtractionError> {
+        if bytes.is_empty() {
+            return Err(ExtractionError::EmptyInput);
+        }
         match Document::from_read(::std::io::Cursor::new(bytes.to_owned())) {
-            Ok(document) => self.process(&document, &self.configuration),
-            _ => self.from_non_utf8_bytes(bytes)
+            Ok(document) => Ok(document),
+            _ => self.decode_non_utf8_bytes(bytes)
         }
     }
 
-    fn from_non_utf8_bytes(self: &Self, bytes: Vec<u8>) -> Option<Article> {
+    fn decode_non_utf8_bytes(&self, bytes: Vec<u8>) -> Result<Document, ExtractionError> {
         let result = detect(&bytes);
         match encoding_from_whatwg_label(charset2encoding(&result.0)) {
             Some(encoding) => {
-                let utf8reader = encoding.decode(&bytes, DecoderTrap::Ignore).expect("Error");
-                match self.pre_process(utf8reader) {
-                    Some(document) => self.process(&document, &self.configuration),
-                    _ => None
-                }
+                let utf8reader = encoding.decode(&bytes, DecoderTrap::Ignore)
+                    .map_err(|cause| ExtractionError::DecodeFailure(cause.into_owned()))?;
+                self.pre_process(utf8reader)
             }
-            _ => None
+            _ => Err(ExtractionError::CharsetNotDetected)
         }
     }
 
-    fn pre_process(self: &Self, raw_html: String) -> Option<Document> {
-        if raw_html == "" {
-            return None;
+    fn pre_process(&self, raw_html: String) -> Result<Document, ExtractionError> {
+        if raw_html.is_empty() {
+            return Err(ExtractionError::EmptyInput);
         }
         let document = Document::from(raw_html.as_str());
-        Some(document)
+        Ok(document)
     }
 
-    fn process(self: &Self, document: &Document, config: &Configuration) -> Option<Article> {
+    fn process(&self, document: &Document, config: &Configuration, page_url: Option<&Url>) -> Result<Article, ExtractionError> {
         let mut article = Article::new();
 
-        article.language = get_language(&document);
+        article.language = get_declared_language(document);
+        article.language_code = get_language(document);
+        if !article.language_code.is_empty() {
+            article.language_declared = true;
+            article.language_confidence = 1.0;
+        } else if let Some((language_code, confidence)) = detect_language(document) {
+            article.language_code = language_code;
+            article.language_confidence = confidence;
+        }
+        let (structured_data, microdata) = if config.enable_meta_extraction || config.enable_text_extraction {
+            (get_structured_data(document), get_microdata(document))
+        } else {
+            (Vec::new(), Vec::new())
+        };
         if config.enable_meta_extraction {
-            article.favico = get_favico(&document);
-            article.canonical_link = get_canonical_link(&document);
-            article.meta_keywords = get_meta_keywords(&document);
-            article.top_image = get_top_image(&document);
+            article.favico = get_favico(document);
+            article.canonical_link = get_canonical_link(document);
+            article.meta_keywords = get_meta_keywords(document);
+            article.oembed_links = get_oembed_links(document);
+            article.top_image = get_top_image(document, &structured_data, &microdata);
+            let (raw_published_at, published_at) = get_published_date(document, &structured_data, &microdata);
+            article.raw_published_at = raw_published_at;
+            article.published_at = published_at;
+            let (raw_modified_at, modified_at) = get_modified_date(document, &structured_data, &microdata);
+            article.raw_modified_at = raw_modified_at;
+            article.modified_at = modified_at;
+            article.authors = get_authors(document, &structured_data, &microdata);
+            article.meta = get_meta(document);
+        }
+        let needs_top_image_fallback = config.enable_meta_extraction && article.top_image.is_empty();
+        let content_node = if config.enable_text_extraction || needs_top_image_fallback {
+            get_content_node(document, &article.language_code, &config.custom_stopwords, &config.boilerplate_patterns)
+        } else {
+            None
+        };
+        if needs_top_image_fallback {
+            article.top_image_candidates = get_top_image_candidates(content_node);
+            if let Some(candidate) = article.top_image_candidates.iter().find(|c| c.selected) {
+                article.top_image = candidate.src.clone();
+            }
         }
         if config.enable_text_extraction {
-            article.title = get_title(&document);
-            let (text, links) = get_text_and_links(&document, article.language.as_ref());
-            article.text = text;
+            article.title = get_title(document, &structured_data, &microdata);
+            let excluded_nodes = get_excluded_nodes(content_node, &config.boilerplate_patterns);
+            let (body, links) = get_body_and_links(document, content_node, &excluded_nodes, &config.text_normalization);
+            article.text = get_blocks_text(&body);
+            article.body = body;
+            if config.enable_markdown_output {
+                article.markdown = get_markdown(document, content_node, &excluded_nodes, page_url);
+            }
+            if config.enable_html_output {
+                article.html = get_clean_html(document, content_node, &config.html_allowlist, &excluded_nodes, page_url);
+            }
             article.links = links;
+            article.images = get_images(content_node);
+            if article.title.trim().is_empty() && article.text.trim().is_empty() {
+                return Err(ExtractionError::NoContentFound);
+            }
+        }
+        if config.enable_meta_extraction {
+            article.structured_data = structured_data;
+            article.microdata = microdata;
         }
         if config.enable_embeddings_extraction {
+            let mut providers: Vec<&dyn EmbedProvider> = BUILTIN_PROVIDERS.iter()
+                .filter(|builtin| !self.providers.iter().any(|p| p.name() == builtin.name()))
+                .copied()
+                .collect();
+            providers.extend(self.providers.iter().map(|p| p.as_ref()));
             article.embeddings = Embeddings {
-                tweets: get_tweets(&document),
-                instagram_posts: get_instagram_posts(&document),
+                posts: get_embeddings(document, &providers),
+                videos: get_videos(document),
             }
         }
-        Some(article)
+        Ok(article)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::fs;
 
+    use select::node::Node;
+
     use crate::configuration::Configuration;
 
     use super::*;
@@ -94,61 +208,319 @@ mod tests {
 
         let raw_html = fs::read_to_string("src/extraction/sites/abcnews.go.com.html")
             .expect("Something went wrong reading the file");
-        let option = extractor.from_string(raw_html);
-        let article = option.unwrap();
+        let result = extractor.from_string(raw_html);
+        let article = result.unwrap();
         assert_eq!(article.title, "New Jersey Devils Owner Apologizes After Landing Helicopter in Middle of Kids' Soccer Game Forces Cancellation - ABC News");
         assert_eq!(article.canonical_link, "http://abcnews.go.com/US/nj-devils-owner-apologizes-landing-helicopter-middle-kids/story?id=35155591");
         assert_eq!(article.meta_keywords, "nj devils owner lands helicopter kids soccer game, helicopter youth soccer game, newark, new jersey, nj nj devils, nhl, josh harris, helicopter cancels soccer game, st benedict preparatory school, sta u13, youth soccer, us news, national news, local news");
         assert_eq!(article.top_image, "http://a.abcnews.go.com/images/US/ht_devils_helicopter_landing_hb_151112_16x9_992.jpg");
         for link in article.links {
-            println!("{}", link);
+            println!("{}", link.href);
         }
     }
 
     #[test]
     fn test_crawl_bizjournal() {
-        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true };
-        let extractor = HtmlExtractor { configuration };
+        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
 
         let raw_html = fs::read_to_string("src/extraction/sites/bizjournals.com.html")
             .expect("Something went wrong reading the file");
-        let option = extractor.from_string(raw_html);
-        let article = option.unwrap();
+        let result = extractor.from_string(raw_html);
+        let article = result.unwrap();
         assert_eq!(article.favico, "http://assets.bizjournals.com/lib/img/favicon.ico");
     }
 
     #[test]
     fn test_crawl_vnexpress() {
-        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true };
-        let extractor = HtmlExtractor { configuration };
+        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
 
         let raw_html = fs::read_to_string("src/extraction/sites/vnexpress.net.html")
             .expect("Something went wrong reading the file");
-        let option = extractor.from_string(raw_html);
-        let article = option.unwrap();
+        let result = extractor.from_string(raw_html);
+        let article = result.unwrap();
         assert_eq!(article.title, "Khánh Ly đến viếng mộ Trịnh Công Sơn - VnExpress Giải Trí");
         assert_eq!(article.language, "vi");
+        assert_eq!(article.language_code, "vi");
+        assert!(article.language_declared);
+        assert_eq!(article.language_confidence, 1.0);
+        assert!(article.text.starts_with("Vừa đặt chân đến Việt Nam chuẩn bị cho buổi hoà nhạc"));
+        assert!(article.text.contains("Tôi chọn hoa hồng màu vàng để tặng anh Sơn"));
+        assert!(article.text.contains("Đêm nhạc dự kiến gồm những ca khúc"));
+    }
+
+    #[test]
+    fn test_crawl_declared_language_tag() {
+        let extractor = HtmlExtractor::default();
+        let raw_html = String::from(r#"<html lang="pt-BR"><head><title>Chuva</title></head><body>
+            <nav><a href="/">Início</a> <a href="/brasil">Brasil</a> <a href="/mundo">Mundo</a></nav>
+            <div class="materia">
+            <p>A previsão é de que a chuva continue durante toda a semana no Rio de Janeiro, e a Defesa Civil pediu que os moradores das áreas de risco fiquem atentos aos alertas.</p>
+            <p>Segundo o instituto, o volume de água acumulado nos últimos dias já é maior do que o esperado para todo o mês de janeiro.</p>
+            </div></body></html>"#);
+        let article = extractor.from_string(raw_html).unwrap();
+        assert_eq!(article.language, "pt-BR");
+        assert_eq!(article.language_code, "pt");
+        assert!(article.language_declared);
+        assert!(article.text.starts_with("A previsão é de que a chuva continue"));
+        assert!(article.text.contains("Segundo o instituto"));
+    }
+
+    #[test]
+    fn test_crawl_detected_language_theguardian() {
+        let extractor = HtmlExtractor::default();
+
+        let raw_html = fs::read_to_string("src/extraction/sites/theguardian.com.html")
+            .expect("Something went wrong reading the file");
+        let article = extractor.from_string(raw_html).unwrap();
+        assert_eq!(article.language, "");
+        assert_eq!(article.language_code, "en");
+        assert!(!article.language_declared);
+        assert!(article.language_confidence > 0.0 && article.language_confidence < 1.0);
+        assert!(!article.text.is_empty());
+        assert_eq!(article.text, get_blocks_text(&article.body));
+    }
+
+    #[test]
+    fn test_crawl_detected_language_without_stopwords() {
+        let extractor = HtmlExtractor::default();
+        let raw_html = String::from(r#"<html><head><title>मानसून</title></head><body>
+            <nav><a href="/">होम</a> <a href="/desh">देश</a> <a href="/videsh">विदेश</a></nav>
+            <div class="story">
+            <p>मौसम विभाग ने बताया कि इस साल मानसून समय से पहले केरल के तट पर पहुंच सकता है और देश के अधिकतर हिस्सों में सामान्य बारिश होगी।</p>
+            <p>किसानों को सलाह दी गई है कि वे बुवाई की तैयारी जल्दी शुरू करें, क्योंकि जून के पहले सप्ताह से ही बारिश शुरू होने की संभावना है।</p>
+            </div></body></html>"#);
+        let article = extractor.from_string(raw_html).unwrap();
+        assert_eq!(article.language, "");
+        assert_eq!(article.language_code, "hi");
+        assert!(!article.language_declared);
+        assert!(article.text.starts_with("मौसम विभाग ने बताया"));
+        assert!(article.text.contains("किसानों को सलाह"));
     }
 
     #[test]
     fn test_crawl_closermag() {
-        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true };
-        let extractor = HtmlExtractor { configuration };
+        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
 
         let raw_html = fs::read_to_string("src/extraction/sites/closermag.fr.html")
             .expect("Something went wrong reading the file");
-        let option = extractor.from_string(raw_html);
-        println!("{}", option.unwrap().text);
+        let result = extractor.from_string(raw_html);
+        println!("{}", result.unwrap().text);
     }
 
     #[test]
     fn test_crawl_charset_koi8_r() {
-        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true };
-        let extractor = HtmlExtractor { configuration };
+        let configuration = Configuration { enable_text_extraction: true, enable_embeddings_extraction: true, enable_meta_extraction: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
 
         let raw_content = fs::read("src/extraction/sites/charset_koi8_r.html")
             .expect("Something went wrong reading the file");
-        let option = extractor.from_bytes(raw_content);
-        println!("{}", option.unwrap().text);
+        let result = extractor.from_bytes(raw_content);
+        println!("{}", result.unwrap().text);
+    }
+
+    fn crawl_charset_fixture(name: &str) -> Article {
+        let configuration = Configuration { enable_text_extraction: true, enable_meta_extraction: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
+        let raw_content = fs::read(format!("src/extraction/sites/charset_{}.html", name))
+            .expect("Something went wrong reading the file");
+        extractor.from_bytes(raw_content).unwrap()
+    }
+
+    #[test]
+    fn test_crawl_charset_shift_jis() {
+        let article = crawl_charset_fixture("shift_jis");
+        assert_eq!(article.title, "文字のエンコードを指定する：HTMLタグ辞典 - HTMLタグボード");
+        assert_eq!(article.language_code, "ja");
+        assert!(article.text.starts_with("HTMLの記述形式（文字コード）を正しく設定することによって、ページが読み込まれたときの文字化けを防ぎます。"));
+    }
+
+    #[test]
+    fn test_crawl_charset_euc_jp() {
+        let article = crawl_charset_fixture("euc_jp");
+        assert_eq!(article.title, "文字コード宣言は行いましょう(HTML) - Web標準普及プロジェクト");
+        assert_eq!(article.language_code, "ja");
+        assert!(article.text.contains("保存する時の文字コードとブラウザが読みとる時に使う文字コードが違っていると文字化けが発生してしまいます。"));
+    }
+
+    #[test]
+    fn test_crawl_charset_euc_kr() {
+        let article = crawl_charset_fixture("euc_kr");
+        assert_eq!(article.title, "httpd-2.2.31.tar.gz: .../examples.html.ko.euc-kr | Fossies");
+        assert!(article.text.starts_with("서버 설정"));
+        assert!(article.text.contains("NameVirtualHost 172.20.30.40"));
+    }
+
+    struct PollProvider;
+
+    impl EmbedProvider for PollProvider {
+        fn name(&self) -> &str { "poll" }
+
+        fn matches(&self, node: &Node) -> bool {
+            node.attr("data-widget") == Some("poll")
+        }
+
+        fn extract(&self, node: &Node) -> Option<Embedding> {
+            Some(Embedding { url: String::from(node.attr("data-url")?), text: node.text(), ..Embedding::default() })
+        }
+    }
+
+    #[test]
+    fn test_register_provider() {
+        let mut extractor = HtmlExtractor::default();
+        extractor.register_provider(PollProvider);
+        let raw_html = String::from(r#"<html><head><title>Title</title></head><body>
+            <div data-widget="poll" data-url="https://polls.example.com/42">Which one?</div>
+            <blockquote class="twitter-tweet"><p>Tweet</p><a href="https://twitter.com/jack/status/20">March 21, 2006</a></blockquote>
+            </body></html>"#);
+        let article = extractor.from_string(raw_html).unwrap();
+        let polls = article.embeddings.get("poll");
+        assert_eq!(polls.len(), 1);
+        assert_eq!(polls[0].provider, "poll");
+        assert_eq!(polls[0].url, "https://polls.example.com/42");
+        assert_eq!(polls[0].text, "Which one?");
+        assert_eq!(article.embeddings.tweets()[0].url, "https://twitter.com/jack/status/20");
+        assert!(article.embeddings.get("facebook").is_empty());
+    }
+
+    struct StubOEmbedResolver {
+        responses: HashMap<&'static str, OEmbed>,
+    }
+
+    impl OEmbedResolver for StubOEmbedResolver {
+        fn resolve(&self, url: &str) -> Option<OEmbed> {
+            self.responses.get(url).cloned()
+        }
+    }
+
+    #[test]
+    fn test_oembed_resolver() {
+        let raw_html = String::from(r#"<html><head><title>Title</title>
+            <link rel="alternate" type="application/json+oembed" href="/oembed?url=https%3A%2F%2Fwww.example.com%2Fpost&format=json">
+            </head><body>
+            <blockquote class="twitter-tweet"><p>Tweet</p>&mdash; Jack (@jack) <a href="https://twitter.com/jack/status/20">March 21, 2006</a></blockquote>
+            <blockquote class="instagram-media"><p><a href="https://www.instagram.com/p/BHA-BtNh3h1/">A post</a></p></blockquote>
+            </body></html>"#);
+        let mut extractor = HtmlExtractor::default();
+        let article = extractor.from_string_with_url(raw_html.clone(), "https://www.example.com/post").unwrap();
+        assert_eq!(article.oembed_links, vec!["https://www.example.com/oembed?url=https%3A%2F%2Fwww.example.com%2Fpost&format=json"]);
+        assert!(article.embeddings.instagram_posts()[0].title.is_empty());
+
+        let oembed = OEmbed {
+            title: String::from("Instagram post"),
+            author_name: String::from("lindsaylohan"),
+            thumbnail_url: String::from("https://www.instagram.com/p/BHA-BtNh3h1/media/"),
+        };
+        let tweet_oembed = OEmbed { author_name: String::from("Someone else"), ..OEmbed::default() };
+        let mut responses = HashMap::new();
+        responses.insert("https://www.instagram.com/p/BHA-BtNh3h1/", oembed);
+        responses.insert("https://twitter.com/jack/status/20", tweet_oembed);
+        extractor.set_oembed_resolver(StubOEmbedResolver { responses });
+        let article = extractor.from_string(raw_html).unwrap();
+        let post = &article.embeddings.instagram_posts()[0];
+        assert_eq!(post.title, "Instagram post");
+        assert_eq!(post.author_name, "lindsaylohan");
+        assert_eq!(post.thumbnail, "https://www.instagram.com/p/BHA-BtNh3h1/media/");
+        assert_eq!(article.embeddings.tweets()[0].author_name, "Jack");
+    }
+
+    #[test]
+    fn test_from_string_empty() {
+        let extractor = HtmlExtractor::default();
+        assert_eq!(extractor.from_string(String::new()), Err(ExtractionError::EmptyInput));
+    }
+
+    #[test]
+    fn test_from_bytes_empty() {
+        let extractor = HtmlExtractor::default();
+        assert_eq!(extractor.from_bytes(Vec::new()), Err(ExtractionError::EmptyInput));
+    }
+
+    #[test]
+    fn test_from_string_no_content() {
+        let extractor = HtmlExtractor::default();
+        let result = extractor.from_string(String::from("<html><body><div></div></body></html>"));
+        assert_eq!(result, Err(ExtractionError::NoContentFound));
+    }
+
+    #[test]
+    fn test_from_string_with_url() {
+        let extractor = HtmlExtractor::default();
+        let raw_html = String::from(r#"<html lang="en"><head>
+            <title>Title</title>
+            <link rel="canonical" href="/news/article.html">
+            <link rel="shortcut icon" href="//cdn.example.com/favicon.ico">
+            <meta property="og:image" content="img/top.jpg">
+            </head><body><div>
+            <p>This is the first paragraph of the article, it is long enough and it has <a href="/first">a link</a> in it.<img src="photo.jpg" srcset="photo-2x.jpg 2x"></p>
+            <p>This is the second paragraph of the article, with <a href="javascript:void(0)">a script</a> and <a href="mailto:me@example.com">a mail</a>.</p>
+            </div></body></html>"#);
+        let article = extractor.from_string_with_url(raw_html, "https://www.example.com/news/article.html?page=1").unwrap();
+        assert_eq!(article.canonical_link, "https://www.example.com/news/article.html");
+        assert_eq!(article.favico, "https://cdn.example.com/favicon.ico");
+        assert_eq!(article.top_image, "https://www.example.com/news/img/top.jpg");
+        let hrefs: Vec<&str> = article.links.iter().map(|link| link.href.as_str()).collect();
+        assert_eq!(hrefs, vec!["https://www.example.com/first"]);
+        assert!(!article.links[0].is_external);
+        assert_eq!(article.images[0].src, "https://www.example.com/news/photo.jpg");
+        assert_eq!(article.images[0].srcset[0].url, "https://www.example.com/news/photo-2x.jpg");
+    }
+
+    #[test]
+    fn test_from_string_huge_image() {
+        let extractor = HtmlExtractor::default();
+        let raw_html = String::from(r#"<html><head><title>Title</title></head><body><div>
+            <p>This is the first paragraph of the article, it is long enough to be the content of the page.</p>
+            <img src="/img/huge.jpg" width="70000" height="70000">
+            </div></body></html>"#);
+        let article = extractor.from_string(raw_html).unwrap();
+        assert_eq!(article.top_image, "/img/huge.jpg");
+    }
+
+    #[test]
+    fn test_from_string_with_url_and_base() {
+        let extractor = HtmlExtractor::default();
+        let raw_html = String::from(r#"<html><head><title>Title</title><base href="https://static.example.com/assets/"><meta property="og:image" content="top.jpg"></head></html>"#);
+        let article = extractor.from_string_with_url(raw_html, "https://www.example.com/news/article.html").unwrap();
+        assert_eq!(article.top_image, "https://static.example.com/assets/top.jpg");
+    }
+
+    #[test]
+    fn test_from_string_with_url_markdown() {
+        let configuration = Configuration { enable_markdown_output: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
+        let raw_html = String::from(r#"<html lang="en"><head><title>Title</title></head><body><div>
+            <h2>The heading of the article</h2>
+            <p>This is the first paragraph of the article, it is long enough and it has <a href="/first">a link</a> in it.</p>
+            <p>This is the second paragraph of the article, with <em>emphasis</em> and an image <img src="photo.jpg" alt="A photo">.</p>
+            </div></body></html>"#);
+        let article = extractor.from_string_with_url(raw_html.clone(), "https://www.example.com/news/article.html").unwrap();
+        assert_eq!(article.markdown, "## The heading of the article\n\n\
+            This is the first paragraph of the article, it is long enough and it has [a link](https://www.example.com/first) in it.\n\n\
+            This is the second paragraph of the article, with *emphasis* and an image ![A photo](https://www.example.com/news/photo.jpg).");
+        assert!(HtmlExtractor::default().from_string(raw_html).unwrap().markdown.is_empty());
+    }
+
+    #[test]
+    fn test_from_string_with_url_html() {
+        let configuration = Configuration { enable_html_output: true, ..Configuration::default() };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
+        let raw_html = String::from(r#"<html lang="en"><head><title>Title</title></head><body><div>
+            <p class="lead">This is the first paragraph of the article, it is long enough and it has <a href="/first" onclick="track()">a link</a> in it.</p>
+            <p>This is the second paragraph of the article, with <em>emphasis</em> and an image <img src="photo.jpg" alt="A photo">.</p>
+            </div></body></html>"#);
+        let article = extractor.from_string_with_url(raw_html.clone(), "https://www.example.com/news/article.html").unwrap();
+        assert_eq!(article.html, "<p>This is the first paragraph of the article, it is long enough and it has <a href=\"https://www.example.com/first\">a link</a> in it.</p>\
+            <p>This is the second paragraph of the article, with <em>emphasis</em> and an image <img src=\"https://www.example.com/news/photo.jpg\" alt=\"A photo\">.</p>");
+        assert!(HtmlExtractor::default().from_string(raw_html).unwrap().html.is_empty());
+    }
+
+    #[test]
+    fn test_from_bytes_with_invalid_url() {
+        let extractor = HtmlExtractor::default();
+        let result = extractor.from_bytes_with_url(b"<html><title>Title</title></html>".to_vec(), "not an url");
+        assert_eq!(result, Err(ExtractionError::InvalidUrl(String::from("not an url"))));
     }
 }
\ No newline at end of file
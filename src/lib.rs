@@ -8,8 +8,9 @@ extern crate serde;
 
 pub mod article;
 pub mod configuration;
+pub mod embedding;
+pub mod error;
 pub mod html;
-mod embedding;
 mod extraction;
 
 #[cfg(test)]
@@ -28,7 +29,7 @@ mod tests {
             .expect("Something went wrong reading the file");
 
         let configuration = Configuration::default();
-        let extractor = HtmlExtractor { configuration };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
         let ptr = raw_html.as_str();
         b.iter(|| extractor.from_string(String::from(ptr)));
     }
@@ -38,7 +39,7 @@ mod tests {
         let raw_html = fs::read_to_string("src/extraction/sites/theguardian.com.html")
             .expect("Something went wrong reading the file");
         let configuration = Configuration::default();
-        let extractor = HtmlExtractor { configuration };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
         let ptr = raw_html.as_str();
         b.iter(|| extractor.from_string(String::from(ptr)));
     }
@@ -48,7 +49,7 @@ mod tests {
         let raw_html = fs::read_to_string("src/extraction/sites/inc.com.html")
             .expect("Something went wrong reading the file");
         let configuration = Configuration::default();
-        let extractor = HtmlExtractor { configuration };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
         let ptr = raw_html.as_str();
         b.iter(|| extractor.from_string(String::from(ptr)));
     }
@@ -59,7 +60,7 @@ mod tests {
             .expect("Something went wrong reading the file");
 
         let configuration = Configuration::default();
-        let extractor = HtmlExtractor { configuration };
+        let extractor = HtmlExtractor { configuration, ..HtmlExtractor::default() };
         b.iter(|| extractor.from_bytes(raw_content.to_vec()));
     }
 